@@ -92,23 +92,45 @@ pub async fn list_project_tables(
     Ok(tables)
 }
 
-async fn get_table(
+/// Fetches the table, or `None` when the API says it doesn't exist. Any other failure is an error.
+async fn find_table(
     client: &Client,
     table_id: &TableReference,
-) -> Result<Table, Box<dyn std::error::Error>> {
+) -> Result<Option<Table>, Box<dyn std::error::Error>> {
     let url = format!(
         "https://bigquery.googleapis.com/bigquery/v2/projects/{}/datasets/{}/tables/{}",
         table_id.project_id, table_id.dataset_id, table_id.table_id
     );
 
-    let table: Table = client.get(&url).send().await?.json::<Table>().await?;
+    let response = client.get(&url).send().await?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        // Error responses look like {"error": {"message": "Access Denied: Table p:d.t: ..."}}.
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let message = body["error"]["message"].as_str().unwrap_or("");
+        return Err(format!("{}: {} {}", table_id.to_str(), status, message).into());
+    }
+
+    let table: Table = response.json::<Table>().await?;
+
+    Ok(Some(table))
+}
 
-    Ok(table)
+async fn get_table(
+    client: &Client,
+    table_id: &TableReference,
+) -> Result<Table, Box<dyn std::error::Error>> {
+    find_table(client, table_id)
+        .await?
+        .ok_or_else(|| format!("{}: table not found", table_id.to_str()).into())
 }
 
 pub async fn get_tables(
     client: &Client,
-    table_ids: &Vec<TableReference>,
+    table_ids: &[TableReference],
 ) -> Result<Vec<Table>, Box<dyn std::error::Error>> {
     let futures = table_ids.iter().map(|table| get_table(client, table));
 
@@ -126,3 +148,21 @@ pub async fn get_tables(
 
     Ok(tables)
 }
+
+/// Like `get_tables`, but fails with the reason the API gave when any of the tables can't be fetched.
+pub async fn try_get_tables(
+    client: &Client,
+    table_ids: &[TableReference],
+) -> Result<Vec<Table>, Box<dyn std::error::Error>> {
+    try_join_all(table_ids.iter().map(|table| get_table(client, table))).await
+}
+
+/// Fetches the tables that exist, leaving out the ones the API reports as not found. Fails on any other error, so
+/// callers can tell a missing table from one that couldn't be checked.
+pub async fn get_existing_tables(
+    client: &Client,
+    table_ids: &[TableReference],
+) -> Result<Vec<Table>, Box<dyn std::error::Error>> {
+    let tables = try_join_all(table_ids.iter().map(|table| find_table(client, table))).await?;
+    Ok(tables.into_iter().flatten().collect())
+}
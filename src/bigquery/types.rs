@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
-// TODO: I'm pretty sure I never need to serialize because I'm only ever reading from the BQ API.
+// These types mirror the BQ API's field names, so they also double as the JSON output format (see render::json).
 
 // Defining serde structs for GET(dataset.list) response.
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,7 +36,160 @@ pub struct Table {
     #[serde(rename = "tableReference")]
     pub table_reference: TableReference,
     // Note: this must be an Option because table.list returns a Table-like type that doesn't contain a schema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub schema: Option<Schema>,
+
+    // Everything below is only returned by table.get.
+    /// TABLE, VIEW, MATERIALIZED_VIEW, EXTERNAL or SNAPSHOT.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub table_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    /// The location of the table's dataset, e.g. `US` or `europe-west2`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    // int64 values are returned as strings by the API.
+    #[serde(rename = "numRows", default, skip_serializing_if = "Option::is_none")]
+    pub num_rows: Option<String>,
+    #[serde(
+        rename = "timePartitioning",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub time_partitioning: Option<TimePartitioning>,
+    #[serde(
+        rename = "rangePartitioning",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub range_partitioning: Option<RangePartitioning>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clustering: Option<Clustering>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view: Option<ViewDefinition>,
+    #[serde(
+        rename = "materializedView",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub materialized_view: Option<ViewDefinition>,
+    #[serde(
+        rename = "tableConstraints",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub table_constraints: Option<TableConstraints>,
+}
+
+impl Table {
+    /// A table with only a reference, as returned by table.list.
+    pub fn from_reference(table_reference: TableReference) -> Self {
+        Table {
+            table_reference,
+            schema: None,
+            table_type: None,
+            description: None,
+            labels: BTreeMap::new(),
+            location: None,
+            num_rows: None,
+            time_partitioning: None,
+            range_partitioning: None,
+            clustering: None,
+            view: None,
+            materialized_view: None,
+            table_constraints: None,
+        }
+    }
+
+    /// The SQL behind a view or materialized view, if this table is one.
+    pub fn view_query(&self) -> Option<&str> {
+        self.view
+            .as_ref()
+            .or(self.materialized_view.as_ref())
+            .map(|view| view.query.as_str())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TimePartitioning {
+    /// DAY, HOUR, MONTH or YEAR.
+    #[serde(rename = "type")]
+    pub partition_type: String,
+    // Missing field means the table is partitioned by ingestion time (_PARTITIONTIME).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(
+        rename = "expirationMs",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expiration_ms: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RangePartitioning {
+    pub field: String,
+    pub range: PartitionRange,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartitionRange {
+    pub start: String,
+    pub end: String,
+    pub interval: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Clustering {
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ViewDefinition {
+    pub query: String,
+    #[serde(
+        rename = "useLegacySql",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub use_legacy_sql: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableConstraints {
+    #[serde(
+        rename = "primaryKey",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub primary_key: Option<PrimaryKey>,
+    #[serde(rename = "foreignKeys", default, skip_serializing_if = "Vec::is_empty")]
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrimaryKey {
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForeignKey {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "referencedTable")]
+    pub referenced_table: TableReference,
+    #[serde(rename = "columnReferences")]
+    pub column_references: Vec<ColumnReference>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColumnReference {
+    #[serde(rename = "referencingColumn")]
+    pub referencing_column: String,
+    #[serde(rename = "referencedColumn")]
+    pub referenced_column: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,9 +207,12 @@ impl TableReference {
     pub fn to_str(&self) -> String {
         format!("{}.{}.{}", self.project_id, self.dataset_id, self.table_id)
     }
+}
 
-    // note: not an impl of FromStr
-    pub fn from_str(s: &str) -> Result<Self, String> {
+impl FromStr for TableReference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts: Vec<&str> = s.split('.').collect();
         if parts.len() != 3 {
             Err(format!("Invalid table reference: {}", s))
@@ -79,6 +237,35 @@ pub struct Column {
     pub name: String,
     #[serde(rename = "type")]
     pub field_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fields: Option<Vec<Column>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl Column {
+    /// The API leaves `mode` out for NULLABLE columns.
+    pub fn mode(&self) -> &str {
+        self.mode.as_deref().unwrap_or("NULLABLE")
+    }
+
+    pub fn is_repeated(&self) -> bool {
+        self.mode() == "REPEATED"
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.mode() == "REQUIRED"
+    }
+
+    /// STRUCT is the standard SQL name for RECORD.
+    pub fn is_record(&self) -> bool {
+        matches!(self.field_type.as_str(), "RECORD" | "STRUCT")
+    }
+
+    /// Nested fields of a RECORD column, empty for everything else.
+    pub fn children(&self) -> &[Column] {
+        self.fields.as_deref().unwrap_or(&[])
+    }
 }
@@ -0,0 +1,138 @@
+//! Data contracts in the Open Data Contract Standard (ODCS) v3 YAML format.
+//!
+//! Only the parts of the standard that describe BigQuery tables are modelled: the servers and the schema objects
+//! with their (nested) properties. Other keys are ignored when a contract is read, so hand-written contracts with
+//! quality rules, SLAs or team sections still load. `render::odcs` writes contracts and `verify` checks live tables
+//! against one.
+
+pub mod verify;
+
+use crate::bigquery::types::TableReference;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Error, Result};
+use std::path::Path;
+
+pub const API_VERSION: &str = "v3.0.2";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataContract {
+    pub api_version: String,
+    pub kind: String,
+    pub id: String,
+    pub version: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<Server>,
+    #[serde(default)]
+    pub schema: Vec<SchemaObject>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Server {
+    pub server: String,
+    #[serde(rename = "type")]
+    pub server_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dataset: Option<String>,
+}
+
+/// A table. `physicalName` holds the full `project.dataset.table` reference.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaObject {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physical_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physical_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logical_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<SchemaProperty>,
+}
+
+/// A column, or the element type of an array under `items`, which has no name.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaProperty {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logical_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub physical_type: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub primary_key: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_key_position: Option<usize>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partitioned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition_key_position: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<SchemaProperty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<SchemaProperty>>,
+}
+
+impl SchemaProperty {
+    pub fn is_array(&self) -> bool {
+        self.items.is_some() || self.logical_type.as_deref() == Some("array")
+    }
+}
+
+impl DataContract {
+    /// The table each schema object describes: its `physicalName` when that's a full reference, otherwise its name
+    /// in the contract's only BigQuery server.
+    pub fn table_reference(&self, object: &SchemaObject) -> Result<TableReference> {
+        let physical_name = object.physical_name.as_deref().unwrap_or(&object.name);
+        if let Ok(reference) = physical_name.parse::<TableReference>() {
+            return Ok(reference);
+        }
+
+        let servers: Vec<&Server> = self
+            .servers
+            .iter()
+            .filter(|server| server.server_type == "bigquery")
+            .collect();
+        match servers.as_slice() {
+            [Server {
+                project: Some(project),
+                dataset: Some(dataset),
+                ..
+            }] => Ok(TableReference {
+                project_id: project.clone(),
+                dataset_id: dataset.clone(),
+                table_id: physical_name.to_string(),
+            }),
+            _ => Err(Error::other(format!(
+                "Can't tell which table schema object {} is: give it a project.dataset.table physicalName",
+                object.name
+            ))),
+        }
+    }
+
+    pub fn table_references(&self) -> Result<Vec<TableReference>> {
+        self.schema
+            .iter()
+            .map(|object| self.table_reference(object))
+            .collect()
+    }
+}
+
+pub fn read_contract(path: &Path) -> Result<DataContract> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| Error::new(err.kind(), format!("{}: {}", path.display(), err)))?;
+    serde_yaml::from_str(&contents)
+        .map_err(|err| Error::other(format!("{}: {}", path.display(), err)))
+}
@@ -0,0 +1,319 @@
+//! Checks live tables against a data contract.
+//!
+//! Breaking violations are missing tables and columns, type changes (including a column becoming or no longer being
+//! REPEATED) and mode changes in either direction: a REQUIRED column turning NULLABLE breaks the contract's promise
+//! to readers, and a NULLABLE one tightening to REQUIRED breaks writers that send NULLs. Removed descriptions are
+//! reported but don't break it. Columns the contract doesn't mention are ignored, so contracts can cover a subset.
+
+use crate::bigquery::types::{Column, Table};
+use crate::contract::{DataContract, SchemaProperty};
+use crate::render::ddl::standard_sql_type;
+use crate::render::odcs::logical_type;
+use std::fmt;
+use std::io::Result;
+
+#[derive(Debug)]
+pub enum ViolationKind {
+    MissingTable,
+    MissingColumn,
+    TypeChanged { expected: String, actual: String },
+    ModeTightened,
+    ModeLoosened,
+    DescriptionRemoved,
+}
+
+#[derive(Debug)]
+pub struct Violation {
+    /// `project.dataset.table`.
+    pub table: String,
+    /// Dotted path of the column, if the violation is about one.
+    pub column: Option<String>,
+    pub kind: ViolationKind,
+}
+
+impl Violation {
+    pub fn is_breaking(&self) -> bool {
+        !matches!(self.kind, ViolationKind::DescriptionRemoved)
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = if self.is_breaking() {
+            "BREAKING"
+        } else {
+            "warning"
+        };
+        write!(f, "[{}] {}", severity, self.table)?;
+        if let Some(column) = &self.column {
+            write!(f, " {}", column)?;
+        }
+        match &self.kind {
+            ViolationKind::MissingTable => write!(f, ": table not found"),
+            ViolationKind::MissingColumn => write!(f, ": column missing"),
+            ViolationKind::TypeChanged { expected, actual } => {
+                write!(f, ": type changed from {} to {}", expected, actual)
+            }
+            ViolationKind::ModeTightened => {
+                write!(f, ": mode tightened to REQUIRED, the contract allows NULLs")
+            }
+            ViolationKind::ModeLoosened => {
+                write!(
+                    f,
+                    ": mode loosened to NULLABLE, the contract requires a value"
+                )
+            }
+            ViolationKind::DescriptionRemoved => write!(f, ": description removed"),
+        }
+    }
+}
+
+/// The BigQuery type a contract property expects, as a standard SQL name with ARRAY<> for arrays. Falls back to the
+/// logical type for contracts written without physical types.
+fn expected_type(property: &SchemaProperty) -> Option<String> {
+    if property.is_array() {
+        let element = property.items.as_deref()?;
+        return expected_type(element).map(|element| format!("ARRAY<{}>", element));
+    }
+    property
+        .physical_type
+        .as_deref()
+        .map(|physical_type| standard_sql_type(&physical_type.to_uppercase()).to_string())
+        .or_else(|| property.logical_type.clone())
+}
+
+/// The live column's type in the same form as `expected_type`, or its logical type when `logical` is set.
+fn actual_type(column: &Column, logical: bool) -> String {
+    let element = if logical {
+        logical_type(&column.field_type).to_string()
+    } else {
+        standard_sql_type(&column.field_type).to_string()
+    };
+    if column.is_repeated() {
+        format!("ARRAY<{}>", element)
+    } else {
+        element
+    }
+}
+
+fn uses_physical_type(property: &SchemaProperty) -> bool {
+    match &property.items {
+        Some(items) if property.is_array() => uses_physical_type(items),
+        _ => property.physical_type.is_some(),
+    }
+}
+
+fn check_columns(
+    table: &str,
+    prefix: &str,
+    properties: &[SchemaProperty],
+    columns: &[Column],
+    violations: &mut Vec<Violation>,
+) {
+    let violation = |path: &str, kind| Violation {
+        table: table.to_string(),
+        column: Some(path.to_string()),
+        kind,
+    };
+
+    for property in properties {
+        let path = format!("{}{}", prefix, property.name);
+        // BigQuery column names are case insensitive.
+        let Some(column) = columns
+            .iter()
+            .find(|column| column.name.eq_ignore_ascii_case(&property.name))
+        else {
+            violations.push(violation(&path, ViolationKind::MissingColumn));
+            continue;
+        };
+
+        if let Some(expected) = expected_type(property) {
+            let actual = actual_type(column, !uses_physical_type(property));
+            if !expected.eq_ignore_ascii_case(&actual) {
+                violations.push(violation(
+                    &path,
+                    ViolationKind::TypeChanged { expected, actual },
+                ));
+            }
+        }
+
+        // Arrays have no mode of their own to compare, REPEATED is part of the type.
+        if !column.is_repeated() && !property.is_array() {
+            match (property.required, column.is_required()) {
+                (false, true) => violations.push(violation(&path, ViolationKind::ModeTightened)),
+                (true, false) => violations.push(violation(&path, ViolationKind::ModeLoosened)),
+                _ => {}
+            }
+        }
+
+        let has_description = |description: &Option<String>| {
+            description
+                .as_deref()
+                .is_some_and(|description| !description.trim().is_empty())
+        };
+        if has_description(&property.description) && !has_description(&column.description) {
+            violations.push(violation(&path, ViolationKind::DescriptionRemoved));
+        }
+
+        let element = match &property.items {
+            Some(items) if property.is_array() => items,
+            _ => property,
+        };
+        if column.is_record() {
+            check_columns(
+                table,
+                &format!("{}.", path),
+                &element.properties,
+                column.children(),
+                violations,
+            );
+        }
+    }
+}
+
+/// Compares `tables` with every table in the contract. Tables that aren't in `tables` are reported as missing.
+pub fn verify(contract: &DataContract, tables: &[Table]) -> Result<Vec<Violation>> {
+    let mut violations = Vec::new();
+    for object in &contract.schema {
+        let reference = contract.table_reference(object)?.to_str();
+        let Some(table) = tables
+            .iter()
+            .find(|table| table.table_reference.to_str() == reference)
+        else {
+            violations.push(Violation {
+                table: reference,
+                column: None,
+                kind: ViolationKind::MissingTable,
+            });
+            continue;
+        };
+
+        let columns = table
+            .schema
+            .as_ref()
+            .map(|schema| schema.fields.as_slice())
+            .unwrap_or(&[]);
+        check_columns(&reference, "", &object.properties, columns, &mut violations);
+    }
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const CONTRACT: &str = "\
+apiVersion: v3.0.2
+kind: DataContract
+id: sales
+version: 1.0.0
+status: active
+schema:
+  - name: orders
+    physicalName: acme.sales.orders
+    properties:
+      - name: id
+        physicalType: INT64
+        required: true
+        description: Order ID
+      - name: total
+        physicalType: NUMERIC
+        description: Order total
+      - name: note
+        logicalType: string
+      - name: status
+        physicalType: STRING
+      - name: gone
+        physicalType: STRING
+      - name: items
+        logicalType: array
+        items:
+          logicalType: object
+          properties:
+            - name: sku
+              physicalType: STRING
+              required: true
+            - name: qty
+              physicalType: INT64
+  - name: refunds
+    physicalName: acme.sales.refunds
+";
+
+    fn orders(fields: serde_json::Value) -> Table {
+        serde_json::from_value(json!({
+            "tableReference": { "projectId": "acme", "datasetId": "sales", "tableId": "orders" },
+            "schema": { "fields": fields }
+        }))
+        .unwrap()
+    }
+
+    fn violations(fields: serde_json::Value) -> Vec<String> {
+        let contract: DataContract = serde_yaml::from_str(CONTRACT).unwrap();
+        verify(&contract, &[orders(fields)])
+            .unwrap()
+            .iter()
+            .map(Violation::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn matching_tables_have_no_violations_but_missing_tables() {
+        let violations = violations(json!([
+            { "name": "ID", "type": "INTEGER", "mode": "REQUIRED", "description": "Order ID" },
+            { "name": "total", "type": "NUMERIC", "description": "Order total" },
+            { "name": "note", "type": "STRING" },
+            { "name": "status", "type": "STRING" },
+            { "name": "gone", "type": "STRING" },
+            { "name": "extra", "type": "BOOL" },
+            { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                { "name": "sku", "type": "STRING", "mode": "REQUIRED" },
+                { "name": "qty", "type": "INTEGER" }
+            ] }
+        ]));
+        assert_eq!(
+            violations,
+            ["[BREAKING] acme.sales.refunds: table not found"]
+        );
+    }
+
+    #[test]
+    fn every_violation_kind_is_reported() {
+        let violations = violations(json!([
+            { "name": "id", "type": "INTEGER" },
+            { "name": "total", "type": "FLOAT" },
+            { "name": "note", "type": "INTEGER" },
+            { "name": "status", "type": "STRING", "mode": "REQUIRED" },
+            { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                { "name": "qty", "type": "INTEGER", "mode": "REPEATED" }
+            ] }
+        ]));
+        assert_eq!(
+            violations,
+            [
+                "[BREAKING] acme.sales.orders id: mode loosened to NULLABLE, the contract requires a value",
+                "[warning] acme.sales.orders id: description removed",
+                "[BREAKING] acme.sales.orders total: type changed from NUMERIC to FLOAT64",
+                "[warning] acme.sales.orders total: description removed",
+                "[BREAKING] acme.sales.orders note: type changed from string to integer",
+                "[BREAKING] acme.sales.orders status: mode tightened to REQUIRED, the contract allows NULLs",
+                "[BREAKING] acme.sales.orders gone: column missing",
+                "[BREAKING] acme.sales.orders items.sku: column missing",
+                "[BREAKING] acme.sales.orders items.qty: type changed from INT64 to ARRAY<INT64>",
+                "[BREAKING] acme.sales.refunds: table not found",
+            ]
+        );
+    }
+
+    #[test]
+    fn only_description_removals_are_warnings() {
+        let violation = |kind| Violation {
+            table: "p.d.t".to_string(),
+            column: None,
+            kind,
+        };
+        assert!(!violation(ViolationKind::DescriptionRemoved).is_breaking());
+        assert!(violation(ViolationKind::MissingTable).is_breaking());
+        assert!(violation(ViolationKind::ModeTightened).is_breaking());
+    }
+}
@@ -32,7 +32,7 @@ pub fn collect_tables(tables: Vec<TableReference>) -> Vec<TableReference> {
 
     let mut tables: Vec<TableReference> = Vec::new();
     for item in selected_items.unwrap().iter() {
-        let table = TableReference::from_str(format!("{}", item.output()).as_str());
+        let table = item.output().parse::<TableReference>();
         match table {
             Ok(table) => tables.push(table),
             Err(e) => eprintln!("Error parsing table reference: {}", e),
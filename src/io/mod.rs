@@ -1,2 +1,3 @@
 pub mod fuzzy;
+pub mod selection;
 pub mod writer;
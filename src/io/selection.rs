@@ -0,0 +1,147 @@
+use crate::bigquery::types::TableReference;
+
+/// True when the table argument is a pattern to match against listed tables rather than a single reference.
+pub fn is_pattern(argument: &str) -> bool {
+    argument.contains(['*', '?'])
+}
+
+/// Matches `project.dataset.table` references against a pattern where `*` matches any run of characters, dots
+/// included, and `?` matches one character. So `my-project.*` selects a whole project and `*.sales.orders_*` a
+/// family of tables across projects.
+pub fn matches_pattern(pattern: &str, reference: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = reference.chars().collect();
+
+    // Iterative wildcard matching, backtracking to the last `*` on a mismatch.
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// The project a pattern is limited to, when its project part has no wildcards.
+pub fn pattern_project(pattern: &str) -> Option<&str> {
+    let (project, _) = pattern.split_once('.')?;
+    (!is_pattern(project)).then_some(project)
+}
+
+/// The tables in `available` matching any of `patterns`, in listing order. Fails when a pattern matches nothing, so
+/// a typo doesn't silently produce an empty selection.
+pub fn select_tables(
+    patterns: &[String],
+    available: Vec<TableReference>,
+) -> Result<Vec<TableReference>, String> {
+    let mut matched = vec![false; patterns.len()];
+    let selected = available
+        .into_iter()
+        .filter(|reference| {
+            let reference = reference.to_str();
+            let mut any = false;
+            for (idx, pattern) in patterns.iter().enumerate() {
+                if matches_pattern(pattern, &reference) {
+                    matched[idx] = true;
+                    any = true;
+                }
+            }
+            any
+        })
+        .collect();
+
+    let unmatched: Vec<&str> = patterns
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(pattern, _)| pattern.as_str())
+        .collect();
+    if !unmatched.is_empty() {
+        return Err(format!("No tables match {}", unmatched.join(", ")));
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal_patterns_match_exactly() {
+        assert!(matches_pattern("p.d.t", "p.d.t"));
+        assert!(!matches_pattern("p.d.t", "p.d.t2"));
+        assert!(!matches_pattern("p.d.t2", "p.d.t"));
+        assert!(!matches_pattern("", "p.d.t"));
+    }
+
+    #[test]
+    fn star_matches_any_run_including_dots_and_nothing() {
+        assert!(matches_pattern("*", "p.d.t"));
+        assert!(matches_pattern("p.*", "p.d.t"));
+        assert!(matches_pattern("*.sales.orders_*", "acme.sales.orders_"));
+        assert!(matches_pattern(
+            "*.sales.orders_*",
+            "acme.sales.orders_2024"
+        ));
+        assert!(!matches_pattern("*.sales.orders_*", "acme.sales.orders"));
+        assert!(matches_pattern("p**t", "p.d.t"));
+        assert!(matches_pattern("*", ""));
+    }
+
+    #[test]
+    fn star_backtracks() {
+        assert!(matches_pattern("*a*b", "xaxbxab"));
+        assert!(matches_pattern("p.*_events.*", "p.web_events_events.t"));
+        assert!(!matches_pattern("*a*b", "xaxbxa"));
+    }
+
+    #[test]
+    fn question_mark_matches_exactly_one_character() {
+        assert!(matches_pattern("p.d.t?", "p.d.t1"));
+        assert!(!matches_pattern("p.d.t?", "p.d.t"));
+        assert!(!matches_pattern("p.d.t?", "p.d.t12"));
+        assert!(matches_pattern("p?d.t", "p.d.t"));
+        assert!(matches_pattern("p.d.caf?", "p.d.café"));
+    }
+
+    #[test]
+    fn pattern_project_needs_a_literal_project() {
+        assert_eq!(pattern_project("acme.sales.*"), Some("acme"));
+        assert_eq!(pattern_project("ac?e.sales.*"), None);
+        assert_eq!(pattern_project("*.sales.orders"), None);
+        assert_eq!(pattern_project("*"), None);
+    }
+
+    #[test]
+    fn select_tables_keeps_listing_order_and_reports_unmatched_patterns() {
+        let available = || -> Vec<TableReference> {
+            ["p.b.t", "p.a.t", "q.a.t"]
+                .iter()
+                .map(|reference| reference.parse().unwrap())
+                .collect()
+        };
+
+        let selected =
+            select_tables(&["*.a.*".to_string(), "p.*".to_string()], available()).unwrap();
+        let selected: Vec<String> = selected.iter().map(TableReference::to_str).collect();
+        assert_eq!(selected, ["p.b.t", "p.a.t", "q.a.t"]);
+
+        let err = select_tables(
+            &["p.*".to_string(), "r.*".to_string(), "*.c.*".to_string()],
+            available(),
+        )
+        .unwrap_err();
+        assert_eq!(err, "No tables match r.*, *.c.*");
+    }
+}
@@ -1,50 +1,192 @@
-use crate::bigquery::types::{Column, Table};
-use std::fs::File;
-use std::io::Write;
-
-fn write_column(file: &mut File, column: &Column, indent: usize) -> std::io::Result<()> {
-    let indent_str = " ".repeat(indent);
-    writeln!(
-        file,
-        "{}|- {} ({}) [{}]",
-        indent_str,
-        column.name,
-        column.field_type,
-        column.mode.as_deref().unwrap_or("REQUIRED")
-    )?;
-
-    // If this is a RECORD type, print its nested fields
-    if let Some(fields) = &column.fields {
-        for field in fields {
-            write_column(file, field, indent + 2)?; // Increase indentation for nested fields
+use crate::bigquery::types::Table;
+use crate::render::{Layout, SchemaRenderer};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Result, Write};
+use std::path::{Path, PathBuf};
+
+/// True for `-`, the output path that means stdout.
+pub fn is_stdout(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Renders `tables` into `out` as a single document.
+///
+/// A table that fails to render is reported and skipped so one bad schema doesn't lose the rest of the output.
+pub fn write_tables(
+    out: &mut dyn Write,
+    renderer: &mut dyn SchemaRenderer,
+    tables: &[Table],
+) -> Result<()> {
+    renderer.begin(out, tables)?;
+
+    for table in tables {
+        if let Err(err) = renderer.render_table(out, table) {
+            eprintln!(
+                "Failed to write table {}: {}",
+                table.table_reference.to_str(),
+                err
+            );
+        }
+    }
+
+    renderer.end(out)?;
+    out.flush()
+}
+
+/// Renders `tables` to stdout. Extra files from the renderer have nowhere to go, so they're reported and skipped.
+fn write_stdout(renderer: &mut dyn SchemaRenderer, tables: &[Table]) -> Result<()> {
+    write_tables(&mut io::stdout().lock(), renderer, tables)?;
+
+    for (relative_path, _) in renderer.extra_files() {
+        eprintln!(
+            "Skipped {}: only the main document is written to stdout",
+            relative_path
+        );
+    }
+
+    Ok(())
+}
+
+/// Renders `tables` into the file at `path`, or stdout for `-`. Any extra files from the renderer are written next
+/// to it, prefixed with the output file's stem.
+pub fn write_file(path: &Path, renderer: &mut dyn SchemaRenderer, tables: &[Table]) -> Result<()> {
+    if is_stdout(path) {
+        return write_stdout(renderer, tables);
+    }
+
+    let mut file = BufWriter::new(File::create(path)?);
+    write_tables(&mut file, renderer, tables)?;
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    for (relative_path, contents) in renderer.extra_files() {
+        fs::write(
+            path.with_file_name(format!("{}.{}", stem, relative_path)),
+            contents,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Renders each table into its own `<project>.<dataset>.<table>.<ext>` file inside `dir`, creating `dir` if needed.
+/// For `-`, the per-table documents are written to stdout one after another.
+pub fn write_table_files(
+    dir: &Path,
+    renderer: &mut dyn SchemaRenderer,
+    tables: &[Table],
+) -> Result<()> {
+    if is_stdout(dir) {
+        for table in tables {
+            write_stdout(renderer, std::slice::from_ref(table))?;
         }
+        return Ok(());
+    }
+
+    fs::create_dir_all(dir)?;
+
+    for table in tables {
+        let path = dir.join(format!(
+            "{}.{}",
+            table.table_reference.to_str(),
+            renderer.extension()
+        ));
+        let mut file = BufWriter::new(File::create(&path)?);
+        write_tables(&mut file, renderer, std::slice::from_ref(table))?;
     }
 
     Ok(())
 }
 
-pub fn write_table(file: &mut File, table: &Table) -> std::io::Result<()> {
-    writeln!(
-        file,
-        "\n=== Table: {}.{}.{} ===",
-        table.table_reference.project_id,
-        table.table_reference.dataset_id,
-        table.table_reference.table_id
-    )?;
-
-    match &table.schema {
-        Some(schema) => {
-            for column in &schema.fields {
-                write_column(file, column, 0)?;
+/// Renders each table into `dir/<project>/<dataset>/<table>.<ext>`, with any extra files next to it as in
+/// `write_file` and the renderer's `dataset_files` once per dataset directory. Formats written as a directory get a
+/// `dir/<project>/<dataset>/<table>/` directory per table instead.
+/// For `-`, this is the same as `write_table_files`.
+pub fn write_table_tree(
+    dir: &Path,
+    renderer: &mut dyn SchemaRenderer,
+    tables: &[Table],
+) -> Result<()> {
+    if is_stdout(dir) {
+        return write_table_files(dir, renderer, tables);
+    }
+
+    // Dataset directory -> the files the renderer shares between its tables.
+    let mut dataset_files: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    for table in tables {
+        let reference = &table.table_reference;
+        let dataset_dir = dir.join(&reference.project_id).join(&reference.dataset_id);
+        if !dataset_files.contains_key(&dataset_dir) {
+            fs::create_dir_all(&dataset_dir)?;
+            let dataset_tables: Vec<&Table> = tables
+                .iter()
+                .filter(|other| {
+                    other.table_reference.project_id == reference.project_id
+                        && other.table_reference.dataset_id == reference.dataset_id
+                })
+                .collect();
+            let mut names = Vec::new();
+            for (name, contents) in renderer.dataset_files(&dataset_tables)? {
+                fs::write(dataset_dir.join(&name), contents)?;
+                names.push(name);
+            }
+            dataset_files.insert(dataset_dir.clone(), names);
+        }
+
+        let file_name = format!("{}.{}", reference.table_id, renderer.extension());
+        if dataset_files[&dataset_dir].contains(&file_name) {
+            return Err(io::Error::other(format!(
+                "{} would overwrite the dataset file {}",
+                reference.to_str(),
+                dataset_dir.join(&file_name).display()
+            )));
+        }
+
+        let table = std::slice::from_ref(table);
+        match renderer.layout() {
+            Layout::Directory { index } => {
+                write_directory(
+                    &dataset_dir.join(&reference.table_id),
+                    index,
+                    renderer,
+                    table,
+                )?;
             }
+            Layout::File | Layout::FilePerTable => {
+                write_file(&dataset_dir.join(file_name), renderer, table)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the main document into `dir/index`, then writes the renderer's extra files next to it. For `-`, only the
+/// main document is written, to stdout.
+pub fn write_directory(
+    dir: &Path,
+    index: &str,
+    renderer: &mut dyn SchemaRenderer,
+    tables: &[Table],
+) -> Result<()> {
+    if is_stdout(dir) {
+        return write_stdout(renderer, tables);
+    }
+
+    fs::create_dir_all(dir)?;
+
+    let mut file = BufWriter::new(File::create(dir.join(index))?);
+    write_tables(&mut file, renderer, tables)?;
+
+    for (relative_path, contents) in renderer.extra_files() {
+        let path = dir.join(relative_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
         }
-        None => writeln!(
-            file,
-            "No schema available for {}.{}.{}",
-            table.table_reference.project_id,
-            table.table_reference.dataset_id,
-            table.table_reference.table_id
-        )?,
+        fs::write(path, contents)?;
     }
 
     Ok(())
@@ -1,2 +1,4 @@
 pub mod bigquery;
+pub mod contract;
 pub mod io;
+pub mod render;
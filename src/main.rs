@@ -1,52 +1,319 @@
+use clap::Parser;
 use colorize::AnsiColor;
 use inquire::Text;
 use schema_searcher::{
-    bigquery::client::{authenticate, get_tables, list_project_tables},
+    bigquery::client::{
+        authenticate, get_existing_tables, get_tables, list_project_tables, try_get_tables,
+    },
+    bigquery::types::{Table, TableReference},
+    contract::{read_contract, verify::verify},
     io::fuzzy,
-    io::writer::write_table,
+    io::selection::{is_pattern, pattern_project, select_tables},
+    io::writer::{write_directory, write_file, write_table_files, write_table_tree},
+    render::{bq_schema::read_schema_dir, Format, Layout, RenderOptions},
 };
-use std::fs::File;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    env_logger::init();
+/// BigQuery Table Schema Collector
+///
+/// Collects table schemas from BigQuery and writes them to a file.
+///
+/// Values that aren't given as flags or environment variables are prompted for when stdin is a terminal. Otherwise
+/// the prompt's default is used, and a missing table selection is an error.
+#[derive(Parser, Debug)]
+struct Args {
+    /// Tables to collect, as comma-separated `<project>.<dataset>.<table>` references or patterns, where `*`
+    /// matches any run of characters and `?` one character, e.g. `my-project.sales.*`. Picked interactively when
+    /// missing.
+    #[arg(short, long, value_delimiter = ',', env = "SCHEMA_SEARCHER_TABLES")]
+    tables: Option<Vec<String>>,
+
+    /// Projects to list tables from, comma-separated. Needed for `--tables` patterns whose project part has a
+    /// wildcard; without `--tables`, the projects to pick tables from.
+    #[arg(short, long, value_delimiter = ',', env = "SCHEMA_SEARCHER_PROJECTS")]
+    projects: Option<Vec<String>>,
+
+    /// The output file, or directory for formats that write several files. `-` writes to stdout.
+    #[arg(short, long, env = "SCHEMA_SEARCHER_OUTPUT_FILE")]
+    output_file: Option<String>,
+
+    /// Path to the service account credentials JSON file. Defaults to `service_account_creds.json`.
+    #[arg(short, long, env = "SCHEMA_SEARCHER_CREDS_PATH")]
+    creds_path: Option<String>,
+
+    /// The output format to write the selected schemas in.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Read tables from a directory of `<project>.<dataset>.<table>.json` bq schema files instead of the BigQuery API.
+    #[arg(long)]
+    schema_dir: Option<PathBuf>,
+
+    /// With `--format dbt`, merge into this existing sources.yml, keeping hand-written tests and other keys.
+    /// It's safe to pass the same path as the output file.
+    #[arg(long)]
+    dbt_merge: Option<PathBuf>,
+
+    /// With `--format llm`, the token budget per output file. Descriptions and deeply nested fields are dropped,
+    /// then the tables are split into several chunk files, until each fits.
+    #[arg(long)]
+    max_tokens: Option<usize>,
+
+    /// With `--format mermaid` or `dot`, also link `<table>_id` columns to the selected table of that name, on top
+    /// of declared foreign keys.
+    #[arg(long)]
+    infer_relationships: bool,
+
+    /// Write one file per table, as `<dir>/<project>/<dataset>/<table>.<ext>`, instead of a single document.
+    #[arg(long)]
+    per_table: bool,
+
+    /// With `--format template`, the Jinja-style template to render. Defaults to a built-in template that matches
+    /// `--format text`.
+    #[arg(long)]
+    template: Option<PathBuf>,
+
+    /// Check the live tables named in this ODCS data contract instead of writing any output. Reports missing
+    /// columns, type and mode changes and removed descriptions, and exits with status 2 on breaking violations (1 is
+    /// for errors, e.g. a table that couldn't be fetched).
+    #[arg(long)]
+    verify: Option<PathBuf>,
+}
+
+/// The flag's value if given. Otherwise the answer to a prompt when stdin is a terminal, or the prompt's default
+/// when it isn't, e.g. in CI.
+fn value_or_prompt(
+    value: Option<&str>,
+    message: &'static str,
+    default: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    match value {
+        Some(value) => Ok(value.to_string()),
+        None if std::io::stdin().is_terminal() => Ok(Text::new(message.yellow().as_str())
+            .with_default(default)
+            .prompt()?),
+        None => Ok(default.to_string()),
+    }
+}
+
+async fn connect(creds_path: Option<&str>) -> Result<reqwest::Client, Box<dyn std::error::Error>> {
+    let creds_path = value_or_prompt(
+        creds_path,
+        "Enter your credentials path",
+        "service_account_creds.json",
+    )?;
+    authenticate(creds_path.as_str()).await
+}
+
+/// Resolves `--tables` to references. Exact references are used as given; patterns are matched against the tables
+/// listed in `--projects` and in the projects the patterns name.
+async fn resolve_tables(
+    client: &reqwest::Client,
+    tables: &[String],
+    projects: Option<&[String]>,
+) -> Result<Vec<TableReference>, Box<dyn std::error::Error>> {
+    let (patterns, references): (Vec<String>, Vec<String>) =
+        tables.iter().cloned().partition(|table| is_pattern(table));
+
+    let mut selected = references
+        .iter()
+        .map(|reference| reference.parse())
+        .collect::<Result<Vec<TableReference>, String>>()?;
+    if patterns.is_empty() {
+        return Ok(selected);
+    }
+
+    let mut project_ids: Vec<String> = projects.unwrap_or_default().to_vec();
+    for pattern in &patterns {
+        match pattern_project(pattern) {
+            Some(project) if !project_ids.iter().any(|id| id == project) => {
+                project_ids.push(project.to_string())
+            }
+            Some(_) => {}
+            None if projects.is_none() => {
+                return Err(format!(
+                    "{} doesn't name a project; pass --projects to say which ones to list",
+                    pattern
+                )
+                .into())
+            }
+            None => {}
+        }
+    }
+
+    let mut available = Vec::new();
+    for project_id in &project_ids {
+        available.extend(list_project_tables(client, project_id).await?);
+    }
+    for reference in select_tables(&patterns, available)? {
+        let id = reference.to_str();
+        if !selected.iter().any(|table| table.to_str() == id) {
+            selected.push(reference);
+        }
+    }
+    Ok(selected)
+}
+
+/// Lists the projects' tables and lets the user pick from them with a fuzzy finder.
+async fn pick_tables(
+    client: &reqwest::Client,
+    projects: Option<&[String]>,
+) -> Result<Vec<TableReference>, Box<dyn std::error::Error>> {
+    let project_ids = match projects {
+        Some(projects) => projects.to_vec(),
+        None => vec![value_or_prompt(
+            None,
+            "Enter your project ID",
+            "bigquery-public-data",
+        )?],
+    };
 
-    let creds_path = Text::new("Enter your credentials path".yellow().as_str())
-        .with_default("service_account_creds.json")
-        .prompt()?;
-    let project_id = Text::new("Enter your project ID".yellow().as_str())
-        .with_default("bigquery-public-data")
-        .prompt()?;
-    let output_path = Text::new(
-        "What would you like the output file to be called?"
-            .yellow()
-            .as_str(),
-    )
-    .with_default("tables.txt")
-    .prompt()?;
+    let mut table_names = Vec::new();
+    for project_id in &project_ids {
+        table_names.extend(list_project_tables(client, project_id).await?);
+    }
+
+    Ok(tokio::task::spawn_blocking(move || fuzzy::collect_tables(table_names)).await?)
+}
+
+async fn fetch_tables(args: &Args) -> Result<Vec<Table>, Box<dyn std::error::Error>> {
+    if args.tables.is_none() && !std::io::stdin().is_terminal() {
+        return Err(
+            "No tables selected; pass --tables or set SCHEMA_SEARCHER_TABLES when stdin isn't a terminal".into(),
+        );
+    }
+
+    let client = connect(args.creds_path.as_deref()).await?;
+    let projects = args.projects.as_deref();
+
+    // Tables asked for by name have to be there, while the picker only offers tables that were just listed.
+    match &args.tables {
+        Some(tables) => {
+            let desired_tables = resolve_tables(&client, tables, projects).await?;
+            try_get_tables(&client, &desired_tables).await
+        }
+        None => {
+            let desired_tables = pick_tables(&client, projects).await?;
+            get_tables(&client, &desired_tables).await
+        }
+    }
+}
+
+/// Checks the tables in the contract and prints every violation. Returns whether any of them are breaking. Tables the
+/// API reports as not found are violations; any other failure to fetch one is an error.
+async fn verify_contract(
+    contract_path: &Path,
+    schema_dir: Option<&Path>,
+    creds_path: Option<&str>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let contract = read_contract(contract_path)?;
+    let references: Vec<TableReference> = contract.table_references()?;
 
-    let client = authenticate(creds_path.as_str()).await?;
+    let tables = match schema_dir {
+        Some(dir) => read_schema_dir(dir)?,
+        None => get_existing_tables(&connect(creds_path).await?, &references).await?,
+    };
 
-    let table_names = list_project_tables(&client, project_id.as_str())
-        .await
-        .unwrap();
+    let violations = verify(&contract, &tables)?;
+    for violation in &violations {
+        println!("{}", violation);
+    }
 
-    let desired_tables =
-        tokio::task::spawn_blocking(move || fuzzy::collect_tables(table_names)).await?;
+    let breaking = violations.iter().filter(|v| v.is_breaking()).count();
+    eprintln!(
+        "{} tables checked: {} breaking, {} other violations",
+        references.len(),
+        breaking,
+        violations.len() - breaking
+    );
+    Ok(breaking > 0)
+}
 
-    let tables_w_schemas = get_tables(&client, &desired_tables).await?;
+fn output_dir(
+    output_file: Option<&str>,
+    default: &str,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let output_dir = value_or_prompt(
+        output_file,
+        "What would you like the output directory to be called? (- for stdout)",
+        default,
+    )?;
+    Ok(PathBuf::from(output_dir))
+}
 
-    let mut output_file = File::create(output_path).expect("Failed to create output file");
+/// The exit status for a contract with breaking violations, distinct from the 1 that errors exit with.
+const EXIT_BREAKING: i32 = 2;
 
-    for table in tables_w_schemas {
-        if let Err(err) = write_table(&mut output_file, &table) {
-            eprintln!(
-                "Failed to write table {}: {}",
-                table.table_reference.to_str(),
-                err
-            );
+async fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(contract_path) = &args.verify {
+        if verify_contract(
+            contract_path,
+            args.schema_dir.as_deref(),
+            args.creds_path.as_deref(),
+        )
+        .await?
+        {
+            std::process::exit(EXIT_BREAKING);
+        }
+        return Ok(());
+    }
+
+    let options = RenderOptions {
+        dbt_merge: args.dbt_merge.clone(),
+        max_tokens: args.max_tokens,
+        infer_relationships: args.infer_relationships,
+        template: args.template.clone(),
+    };
+    let mut renderer = args.format.renderer(&options)?;
+
+    let tables_w_schemas = match &args.schema_dir {
+        Some(dir) => read_schema_dir(dir)?,
+        None => fetch_tables(&args).await?,
+    };
+
+    if args.per_table {
+        let output_dir = output_dir(args.output_file.as_deref(), "schemas")?;
+        write_table_tree(&output_dir, renderer.as_mut(), &tables_w_schemas)?;
+        return Ok(());
+    }
+
+    match renderer.layout() {
+        Layout::File => {
+            let default_output_path = renderer.default_file_name();
+            let output_path = value_or_prompt(
+                args.output_file.as_deref(),
+                "What would you like the output file to be called? (- for stdout)",
+                &default_output_path,
+            )?;
+
+            write_file(
+                Path::new(&output_path),
+                renderer.as_mut(),
+                &tables_w_schemas,
+            )?;
+        }
+        Layout::FilePerTable => {
+            let output_dir = output_dir(args.output_file.as_deref(), "schemas")?;
+            write_table_files(&output_dir, renderer.as_mut(), &tables_w_schemas)?;
+        }
+        Layout::Directory { index } => {
+            let output_dir = output_dir(args.output_file.as_deref(), "site")?;
+            write_directory(&output_dir, index, renderer.as_mut(), &tables_w_schemas)?;
         }
     }
 
     Ok(())
 }
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    // Printed with Display rather than returned from main, which would use Debug and mangle multi-line messages
+    // like template errors.
+    if let Err(err) = run(Args::parse()).await {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,206 @@
+//! Apache Arrow schemas, using the types the BigQuery Storage Read API returns.
+//!
+//! The schema is written in Arrow's JSON schema representation (the one used by the Arrow integration tests):
+//! a `fields` array where each field has `name`, `nullable`, `type` and `children`. GEOGRAPHY and JSON columns are
+//! `utf8` with the same `ARROW:extension:name` metadata the Read API sets. A single table is written as one schema;
+//! several tables are written as an object keyed by `project.dataset.table`.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::SchemaRenderer;
+use serde_json::{json, Map, Value};
+use std::io::{Result, Write};
+
+#[derive(Default)]
+pub struct ArrowRenderer {
+    schemas: Vec<(String, Value)>,
+}
+
+fn arrow_type(field_type: &str) -> Value {
+    match field_type {
+        "BOOLEAN" | "BOOL" => json!({ "name": "bool" }),
+        "INTEGER" | "INT64" => json!({ "name": "int", "bitWidth": 64, "isSigned": true }),
+        "FLOAT" | "FLOAT64" => json!({ "name": "floatingpoint", "precision": "DOUBLE" }),
+        "NUMERIC" | "DECIMAL" => {
+            json!({ "name": "decimal", "precision": 38, "scale": 9, "bitWidth": 128 })
+        }
+        "BIGNUMERIC" | "BIGDECIMAL" => {
+            json!({ "name": "decimal", "precision": 76, "scale": 38, "bitWidth": 256 })
+        }
+        "BYTES" => json!({ "name": "binary" }),
+        "DATE" => json!({ "name": "date", "unit": "DAY" }),
+        "TIME" => json!({ "name": "time", "unit": "MICROSECOND", "bitWidth": 64 }),
+        "DATETIME" => json!({ "name": "timestamp", "unit": "MICROSECOND" }),
+        "TIMESTAMP" => json!({ "name": "timestamp", "unit": "MICROSECOND", "timezone": "UTC" }),
+        _ => json!({ "name": "utf8" }),
+    }
+}
+
+fn extension_name(field_type: &str) -> Option<&'static str> {
+    match field_type {
+        "GEOGRAPHY" => Some("google:sqlType:geography"),
+        "JSON" => Some("google:sqlType:json"),
+        _ => None,
+    }
+}
+
+/// The field for a single value of the column, ignoring REPEATED.
+fn value_field(column: &Column, name: &str, nullable: bool) -> Value {
+    let (field_type, children) = if column.is_record() {
+        let children: Vec<Value> = column.children().iter().map(arrow_field).collect();
+        (json!({ "name": "struct" }), children)
+    } else {
+        (arrow_type(&column.field_type), Vec::new())
+    };
+
+    let mut field = json!({
+        "name": name,
+        "nullable": nullable,
+        "type": field_type,
+        "children": children,
+    });
+    if let Some(extension) = extension_name(&column.field_type) {
+        field["metadata"] = json!([{ "key": "ARROW:extension:name", "value": extension }]);
+    }
+    field
+}
+
+fn arrow_field(column: &Column) -> Value {
+    if column.is_repeated() {
+        // BigQuery arrays and their elements are never NULL.
+        json!({
+            "name": column.name,
+            "nullable": false,
+            "type": { "name": "list" },
+            "children": [value_field(column, "item", false)],
+        })
+    } else {
+        value_field(column, &column.name, !column.is_required())
+    }
+}
+
+fn arrow_schema(table: &Table) -> Value {
+    let fields: Vec<Value> = table
+        .schema
+        .as_ref()
+        .map(|schema| schema.fields.iter().map(arrow_field).collect())
+        .unwrap_or_default();
+    json!({ "fields": fields })
+}
+
+impl SchemaRenderer for ArrowRenderer {
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        self.schemas
+            .push((table.table_reference.to_str(), arrow_schema(table)));
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        let document = if self.schemas.len() == 1 {
+            self.schemas.remove(0).1
+        } else {
+            Value::Object(self.schemas.drain(..).collect::<Map<String, Value>>())
+        };
+
+        serde_json::to_writer_pretty(&mut *out, &document)?;
+        writeln!(out)
+    }
+
+    fn extension(&self) -> &'static str {
+        "arrow.json"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+
+    fn table(reference: &str, fields: Value) -> Table {
+        let mut table = Table::from_reference(reference.parse().unwrap());
+        table.schema = Some(serde_json::from_value(json!({ "fields": fields })).unwrap());
+        table
+    }
+
+    fn render(tables: &[Table]) -> Value {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut ArrowRenderer::default(), tables).unwrap();
+        serde_json::from_slice(&out).unwrap()
+    }
+
+    #[test]
+    fn types_follow_the_storage_read_api() {
+        let schema = render(&[table(
+            "p.d.t",
+            json!([
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+                { "name": "total", "type": "BIGNUMERIC" },
+                { "name": "created_at", "type": "TIMESTAMP" },
+                { "name": "local_at", "type": "DATETIME" },
+                { "name": "area", "type": "GEOGRAPHY" }
+            ]),
+        )]);
+        let fields = schema["fields"].as_array().unwrap();
+
+        assert_eq!(
+            fields[0],
+            json!({
+                "name": "id",
+                "nullable": false,
+                "type": { "name": "int", "bitWidth": 64, "isSigned": true },
+                "children": []
+            })
+        );
+        assert_eq!(
+            fields[1]["type"],
+            json!({ "name": "decimal", "precision": 76, "scale": 38, "bitWidth": 256 })
+        );
+        assert_eq!(fields[1]["nullable"], true);
+        assert_eq!(fields[2]["type"]["timezone"], "UTC");
+        assert!(fields[3]["type"].get("timezone").is_none());
+        assert_eq!(fields[4]["type"], json!({ "name": "utf8" }));
+        assert_eq!(
+            fields[4]["metadata"],
+            json!([{ "key": "ARROW:extension:name", "value": "google:sqlType:geography" }])
+        );
+    }
+
+    #[test]
+    fn repeated_records_are_lists_of_structs() {
+        let schema = render(&[table(
+            "p.d.t",
+            json!([{ "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                { "name": "sku", "type": "STRING", "mode": "REQUIRED" }
+            ] }]),
+        )]);
+
+        assert_eq!(
+            schema["fields"][0],
+            json!({
+                "name": "items",
+                "nullable": false,
+                "type": { "name": "list" },
+                "children": [{
+                    "name": "item",
+                    "nullable": false,
+                    "type": { "name": "struct" },
+                    "children": [{
+                        "name": "sku",
+                        "nullable": false,
+                        "type": { "name": "utf8" },
+                        "children": []
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn several_tables_are_keyed_by_reference() {
+        let fields = json!([{ "name": "id", "type": "INTEGER" }]);
+        let schema = render(&[table("p.d.a", fields.clone()), table("p.d.b", fields)]);
+
+        let keys: Vec<&String> = schema.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["p.d.a", "p.d.b"]);
+        assert_eq!(schema["p.d.b"]["fields"][0]["name"], "id");
+    }
+}
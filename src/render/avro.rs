@@ -0,0 +1,264 @@
+//! Avro schema (`.avsc`) output, following BigQuery's own Avro export mapping with logical types enabled.
+//!
+//! A single table is written as one record schema. Several tables are written as a JSON array, which Avro reads as a
+//! union of the records.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::SchemaRenderer;
+use serde_json::{json, Value};
+use std::io::{Result, Write};
+
+#[derive(Default)]
+pub struct AvroRenderer {
+    records: Vec<Value>,
+}
+
+/// Avro names must match `[A-Za-z_][A-Za-z0-9_]*`, which project IDs with dashes don't.
+fn avro_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) || sanitized.is_empty() {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn record_schema(name: &str, namespace: &str, doc: Option<&String>, columns: &[Column]) -> Value {
+    // Nested records are namespaced under their parent so two RECORDs with the same field name don't collide.
+    let child_namespace = format!("{}.{}", namespace, name);
+    let fields: Vec<Value> = columns
+        .iter()
+        .map(|column| field_schema(column, &child_namespace))
+        .collect();
+
+    let mut record = json!({
+        "type": "record",
+        "name": name,
+        "namespace": namespace,
+    });
+    if let Some(doc) = doc {
+        record["doc"] = json!(doc);
+    }
+    record["fields"] = Value::Array(fields);
+    record
+}
+
+fn value_schema(column: &Column, namespace: &str) -> Value {
+    if column.is_record() {
+        return record_schema(&avro_name(&column.name), namespace, None, column.children());
+    }
+
+    match column.field_type.as_str() {
+        "INTEGER" | "INT64" => json!("long"),
+        "FLOAT" | "FLOAT64" => json!("double"),
+        "BOOLEAN" | "BOOL" => json!("boolean"),
+        "BYTES" => json!("bytes"),
+        "NUMERIC" | "DECIMAL" => {
+            json!({ "type": "bytes", "logicalType": "decimal", "precision": 38, "scale": 9 })
+        }
+        "BIGNUMERIC" | "BIGDECIMAL" => {
+            json!({ "type": "bytes", "logicalType": "decimal", "precision": 77, "scale": 38 })
+        }
+        "DATE" => json!({ "type": "int", "logicalType": "date" }),
+        "TIME" => json!({ "type": "long", "logicalType": "time-micros" }),
+        "TIMESTAMP" => json!({ "type": "long", "logicalType": "timestamp-micros" }),
+        "DATETIME" => json!({ "type": "string", "logicalType": "datetime" }),
+        "GEOGRAPHY" => json!({ "type": "string", "sqlType": "GEOGRAPHY" }),
+        "JSON" => json!({ "type": "string", "sqlType": "JSON" }),
+        _ => json!("string"),
+    }
+}
+
+fn field_schema(column: &Column, namespace: &str) -> Value {
+    let value = value_schema(column, namespace);
+
+    let mut field = json!({ "name": avro_name(&column.name) });
+    if let Some(description) = &column.description {
+        field["doc"] = json!(description);
+    }
+
+    if column.is_repeated() {
+        field["type"] = json!({ "type": "array", "items": value });
+    } else if column.is_required() {
+        field["type"] = value;
+    } else {
+        field["type"] = json!(["null", value]);
+        field["default"] = Value::Null;
+    }
+    field
+}
+
+fn table_schema(table: &Table) -> Value {
+    let reference = &table.table_reference;
+    let namespace = format!(
+        "{}.{}",
+        avro_name(&reference.project_id),
+        avro_name(&reference.dataset_id)
+    );
+    let columns = table
+        .schema
+        .as_ref()
+        .map(|schema| schema.fields.as_slice())
+        .unwrap_or(&[]);
+
+    record_schema(
+        &avro_name(&reference.table_id),
+        &namespace,
+        table.description.as_ref(),
+        columns,
+    )
+}
+
+impl SchemaRenderer for AvroRenderer {
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        self.records.push(table_schema(table));
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        let document = if self.records.len() == 1 {
+            self.records.remove(0)
+        } else {
+            Value::Array(self.records.drain(..).collect())
+        };
+
+        serde_json::to_writer_pretty(&mut *out, &document)?;
+        writeln!(out)
+    }
+
+    fn extension(&self) -> &'static str {
+        "avsc"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+
+    fn table(reference: &str, fields: Value) -> Table {
+        let mut table = Table::from_reference(reference.parse().unwrap());
+        table.schema = Some(serde_json::from_value(json!({ "fields": fields })).unwrap());
+        table
+    }
+
+    fn render(tables: &[Table]) -> Value {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut AvroRenderer::default(), tables).unwrap();
+        serde_json::from_slice(&out).unwrap()
+    }
+
+    /// The type of each field of a single table, by name.
+    fn field_types(fields: Value) -> Vec<(String, Value)> {
+        let schema = render(&[table("p.d.t", fields)]);
+        schema["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| {
+                (
+                    field["name"].as_str().unwrap().to_string(),
+                    field["type"].clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn logical_types_follow_the_bigquery_export() {
+        let types = field_types(json!([
+            { "name": "numeric", "type": "NUMERIC", "mode": "REQUIRED" },
+            { "name": "bignumeric", "type": "BIGNUMERIC", "mode": "REQUIRED" },
+            { "name": "date", "type": "DATE", "mode": "REQUIRED" },
+            { "name": "time", "type": "TIME", "mode": "REQUIRED" },
+            { "name": "timestamp", "type": "TIMESTAMP", "mode": "REQUIRED" },
+            { "name": "datetime", "type": "DATETIME", "mode": "REQUIRED" },
+            { "name": "geography", "type": "GEOGRAPHY", "mode": "REQUIRED" },
+            { "name": "integer", "type": "INTEGER", "mode": "REQUIRED" },
+            { "name": "float", "type": "FLOAT", "mode": "REQUIRED" }
+        ]));
+
+        assert_eq!(
+            types,
+            [
+                ("numeric", json!({ "type": "bytes", "logicalType": "decimal", "precision": 38, "scale": 9 })),
+                ("bignumeric", json!({ "type": "bytes", "logicalType": "decimal", "precision": 77, "scale": 38 })),
+                ("date", json!({ "type": "int", "logicalType": "date" })),
+                ("time", json!({ "type": "long", "logicalType": "time-micros" })),
+                ("timestamp", json!({ "type": "long", "logicalType": "timestamp-micros" })),
+                ("datetime", json!({ "type": "string", "logicalType": "datetime" })),
+                ("geography", json!({ "type": "string", "sqlType": "GEOGRAPHY" })),
+                ("integer", json!("long")),
+                ("float", json!("double")),
+            ]
+            .map(|(name, schema)| (name.to_string(), schema))
+        );
+    }
+
+    #[test]
+    fn nullable_fields_are_unions_with_a_null_default() {
+        let schema = render(&[table(
+            "p.d.t",
+            json!([
+                { "name": "note", "type": "STRING", "description": "Free text" },
+                { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+                { "name": "day", "type": "DATE" }
+            ]),
+        )]);
+        let fields = schema["fields"].as_array().unwrap();
+
+        assert_eq!(
+            fields[0],
+            json!({ "name": "note", "doc": "Free text", "type": ["null", "string"], "default": null })
+        );
+        // Arrays can't be null in BigQuery, so they aren't unions.
+        assert_eq!(
+            fields[1],
+            json!({ "name": "tags", "type": { "type": "array", "items": "string" } })
+        );
+        assert_eq!(
+            fields[2]["type"],
+            json!(["null", { "type": "int", "logicalType": "date" }])
+        );
+    }
+
+    #[test]
+    fn records_are_named_and_namespaced() {
+        let schema = render(&[table(
+            "my-project.sales.order-lines",
+            json!([
+                { "name": "address", "type": "RECORD", "mode": "REQUIRED", "fields": [
+                    { "name": "geo", "type": "RECORD", "mode": "REQUIRED", "fields": [
+                        { "name": "lat", "type": "FLOAT", "mode": "REQUIRED" }
+                    ] }
+                ] }
+            ]),
+        )]);
+
+        assert_eq!(schema["name"], "order_lines");
+        assert_eq!(schema["namespace"], "my_project.sales");
+        let address = &schema["fields"][0]["type"];
+        assert_eq!(address["name"], "address");
+        assert_eq!(address["namespace"], "my_project.sales.order_lines");
+        assert_eq!(
+            address["fields"][0]["type"]["namespace"],
+            "my_project.sales.order_lines.address"
+        );
+    }
+
+    #[test]
+    fn several_tables_are_a_union() {
+        let fields = json!([{ "name": "id", "type": "INTEGER", "mode": "REQUIRED" }]);
+        let schema = render(&[table("p.d.a", fields.clone()), table("p.d.b", fields)]);
+
+        let names: Vec<&str> = schema
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|record| record["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["a", "b"]);
+    }
+}
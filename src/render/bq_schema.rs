@@ -0,0 +1,187 @@
+//! The JSON schema file format used by `bq mk --schema` and the load tooling: a bare array of fields, as printed by
+//! `bq show --schema --format=prettyjson`. Each table gets its own `<project>.<dataset>.<table>.json` file.
+
+use crate::bigquery::types::{Column, Schema, Table, TableReference};
+use crate::render::{Layout, SchemaRenderer};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Error, Result, Write};
+use std::path::Path;
+
+pub struct BqSchemaRenderer;
+
+// bq sorts keys alphabetically, so the fields here are declared in that order rather than reusing `Column`.
+/// One field of a schema file. Every field gets an explicit `mode`, as `bq show` and the API print them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BqField {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fields: Option<Vec<BqField>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    name: String,
+    #[serde(rename = "type")]
+    field_type: String,
+}
+
+impl From<&Column> for BqField {
+    fn from(column: &Column) -> Self {
+        BqField {
+            description: column.description.clone(),
+            fields: column
+                .fields
+                .as_ref()
+                .map(|fields| fields.iter().map(BqField::from).collect()),
+            mode: Some(column.mode().to_string()),
+            name: column.name.clone(),
+            field_type: column.field_type.clone(),
+        }
+    }
+}
+
+impl From<BqField> for Column {
+    fn from(field: BqField) -> Self {
+        Column {
+            name: field.name,
+            field_type: field.field_type,
+            mode: field.mode,
+            fields: field
+                .fields
+                .map(|fields| fields.into_iter().map(Column::from).collect()),
+            description: field.description,
+        }
+    }
+}
+
+impl SchemaRenderer for BqSchemaRenderer {
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let fields: Vec<BqField> = match &table.schema {
+            Some(schema) => schema.fields.iter().map(BqField::from).collect(),
+            None => Vec::new(),
+        };
+        serde_json::to_writer_pretty(&mut *out, &fields)?;
+        writeln!(out)
+    }
+
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn layout(&self) -> Layout {
+        Layout::FilePerTable
+    }
+}
+
+/// Reads a single schema file. The table reference comes from the `<project>.<dataset>.<table>.json` file name.
+pub fn read_schema_file(path: &Path) -> Result<Table> {
+    let reference = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| Error::other(format!("Invalid file name: {}", path.display())))?
+        .parse::<TableReference>()
+        .map_err(Error::other)?;
+
+    let contents = fs::read_to_string(path)?;
+    let fields: Vec<BqField> = serde_json::from_str(&contents)
+        .map_err(|err| Error::other(format!("{}: {}", path.display(), err)))?;
+
+    let mut table = Table::from_reference(reference);
+    table.schema = Some(Schema {
+        fields: fields.into_iter().map(Column::from).collect(),
+    });
+    Ok(table)
+}
+
+/// Reads every `*.json` schema file in `dir`, sorted by file name.
+pub fn read_schema_dir(dir: &Path) -> Result<Vec<Table>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths.iter().map(|path| read_schema_file(path)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_table_files;
+    use serde_json::json;
+
+    #[test]
+    fn schema_files_round_trip() {
+        let fields = json!([
+            { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Primary key" },
+            { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+            { "name": "address", "type": "RECORD", "mode": "NULLABLE", "fields": [
+                { "name": "city", "type": "STRING", "mode": "NULLABLE" }
+            ] }
+        ]);
+        let tables: Vec<Table> = ["acme.sales.orders", "acme.ops.events"]
+            .into_iter()
+            .map(|reference| {
+                let mut table = Table::from_reference(reference.parse().unwrap());
+                table.schema = Some(serde_json::from_value(json!({ "fields": fields })).unwrap());
+                table
+            })
+            .collect();
+
+        let dir =
+            std::env::temp_dir().join(format!("schema-searcher-bq-schema-{}", std::process::id()));
+        write_table_files(&dir, &mut BqSchemaRenderer, &tables).unwrap();
+        let written = fs::read_to_string(dir.join("acme.sales.orders.json")).unwrap();
+        let read = read_schema_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // Each file is the bare field array, keys sorted like `bq show` prints them.
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&written).unwrap(),
+            fields
+        );
+        assert!(written.find("\"description\"") < written.find("\"name\""));
+
+        // Sorted by file name.
+        let references: Vec<String> = read
+            .iter()
+            .map(|table| table.table_reference.to_str())
+            .collect();
+        assert_eq!(references, ["acme.ops.events", "acme.sales.orders"]);
+        for table in &read {
+            assert_eq!(
+                serde_json::to_value(&table.schema).unwrap()["fields"],
+                fields
+            );
+        }
+    }
+
+    #[test]
+    fn missing_modes_are_written_as_nullable() {
+        let column: Column =
+            serde_json::from_value(json!({ "name": "id", "type": "STRING" })).unwrap();
+        let field = serde_json::to_value(BqField::from(&column)).unwrap();
+        assert_eq!(
+            field,
+            json!({ "name": "id", "type": "STRING", "mode": "NULLABLE" })
+        );
+    }
+
+    #[test]
+    fn file_names_must_be_table_references() {
+        let dir = std::env::temp_dir().join(format!(
+            "schema-searcher-bq-schema-name-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("orders.json");
+        fs::write(&path, "[]").unwrap();
+        let result = read_schema_file(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Flat column catalogs as CSV or TSV: one row per column, nested fields included, for spreadsheets and `grep`.
+
+use crate::bigquery::types::Table;
+use crate::render::{flatten_columns, SchemaRenderer};
+use std::io::{Error, Result, Write};
+
+const HEADER: [&str; 9] = [
+    "project",
+    "dataset",
+    "table",
+    "path",
+    "depth",
+    "type",
+    "mode",
+    "repeated_ancestor",
+    "description",
+];
+
+pub struct CatalogRenderer {
+    delimiter: u8,
+}
+
+impl CatalogRenderer {
+    pub fn csv() -> Self {
+        CatalogRenderer { delimiter: b',' }
+    }
+
+    pub fn tsv() -> Self {
+        CatalogRenderer { delimiter: b'\t' }
+    }
+
+    fn writer<'a>(&self, out: &'a mut dyn Write) -> csv::Writer<&'a mut dyn Write> {
+        csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(out)
+    }
+}
+
+impl SchemaRenderer for CatalogRenderer {
+    fn begin(&mut self, out: &mut dyn Write, _tables: &[Table]) -> Result<()> {
+        let mut writer = self.writer(out);
+        writer.write_record(HEADER).map_err(Error::other)?;
+        writer.flush()
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let Some(schema) = &table.schema else {
+            return Ok(());
+        };
+
+        let reference = &table.table_reference;
+        let mut writer = self.writer(out);
+        for flat in flatten_columns(&schema.fields) {
+            writer
+                .write_record([
+                    reference.project_id.as_str(),
+                    reference.dataset_id.as_str(),
+                    reference.table_id.as_str(),
+                    flat.path.as_str(),
+                    &flat.depth.to_string(),
+                    flat.column.field_type.as_str(),
+                    flat.column.mode(),
+                    if flat.repeated_ancestor {
+                        "true"
+                    } else {
+                        "false"
+                    },
+                    flat.column.description.as_deref().unwrap_or(""),
+                ])
+                .map_err(Error::other)?;
+        }
+        writer.flush()
+    }
+
+    fn end(&mut self, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+
+    fn extension(&self) -> &'static str {
+        if self.delimiter == b'\t' {
+            "tsv"
+        } else {
+            "csv"
+        }
+    }
+
+    fn default_file_name(&self) -> String {
+        format!("columns.{}", self.extension())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn tables() -> Vec<Table> {
+        let mut orders = Table::from_reference("p.sales.orders".parse().unwrap());
+        orders.schema = Some(
+            serde_json::from_value(json!({ "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key, \"unique\"" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                    { "name": "sku", "type": "STRING" }
+                ] }
+            ] }))
+            .unwrap(),
+        );
+        let view = Table::from_reference("p.sales.no_schema".parse().unwrap());
+        vec![orders, view]
+    }
+
+    fn render(renderer: &mut CatalogRenderer) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, renderer, &tables()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn csv_lists_every_column_by_path() {
+        assert_eq!(
+            render(&mut CatalogRenderer::csv()),
+            "project,dataset,table,path,depth,type,mode,repeated_ancestor,description
+p,sales,orders,id,0,INTEGER,REQUIRED,false,\"Key, \"\"unique\"\"\"
+p,sales,orders,items,0,RECORD,REPEATED,false,
+p,sales,orders,items.sku,1,STRING,NULLABLE,true,
+"
+        );
+    }
+
+    #[test]
+    fn tsv_uses_tabs() {
+        let output = render(&mut CatalogRenderer::tsv());
+
+        assert!(output.starts_with("project\tdataset\ttable\tpath\t"));
+        assert!(output.contains("p\tsales\torders\titems.sku\t1\tSTRING\tNULLABLE\ttrue\t\n"));
+        assert_eq!(output.lines().count(), 4);
+    }
+}
@@ -0,0 +1,324 @@
+//! DataHub metadata change proposals (MCPs), in the JSON array format read by DataHub's `file` ingestion source.
+//!
+//! Each table is a DataHub dataset with the URN DataHub's own BigQuery source uses
+//! (`urn:li:dataset:(urn:li:dataPlatform:bigquery,project.dataset.table,PROD)`), in a container for its BigQuery
+//! dataset whose URN is derived the same way the source derives it, see `container_urn`. Datasets get
+//! `datasetProperties`, `subTypes`, `schemaMetadata` with a field per column (nested fields by dotted path),
+//! `viewProperties` for views, and `container`.
+//!
+//! Every proposal UPSERTs a whole aspect. For entities the BigQuery source has ingested too, whichever ran last wins
+//! each aspect: loading this file replaces the source's `schemaMetadata` and properties, with anything only the
+//! source fills in, and the next ingestion run replaces them back.
+
+use crate::bigquery::types::{Column, Table, TableReference};
+use crate::render::ddl::standard_sql_type;
+use crate::render::{flatten_columns, SchemaRenderer};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{Result, Write};
+
+const PLATFORM: &str = "urn:li:dataPlatform:bigquery";
+const ENV: &str = "PROD";
+
+#[derive(Default)]
+pub struct DatahubRenderer {
+    proposals: Vec<Value>,
+    containers: HashSet<String>,
+}
+
+fn dataset_urn(reference: &TableReference) -> String {
+    format!(
+        "urn:li:dataset:({},{},{})",
+        PLATFORM,
+        reference.to_str(),
+        ENV
+    )
+}
+
+/// The URN of the container for the table's BigQuery dataset. DataHub's BigQuery source names it with the MD5 of its
+/// `BigQueryDatasetKey` as compact JSON with sorted keys. For backwards compatibility that key carries the env as its
+/// `instance` when no platform instance is configured, which this assumes.
+fn container_urn(reference: &TableReference) -> String {
+    let key: BTreeMap<&str, &str> = [
+        ("dataset_id", reference.dataset_id.as_str()),
+        ("instance", ENV),
+        ("platform", "bigquery"),
+        ("project_id", reference.project_id.as_str()),
+    ]
+    .into_iter()
+    .collect();
+    let key = serde_json::to_string(&key).unwrap_or_default();
+
+    // Python's json.dumps escapes non-ASCII characters, and the hash is over its output.
+    let mut ascii_key = String::new();
+    for c in key.chars() {
+        if c.is_ascii() {
+            ascii_key.push(c);
+        } else {
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                ascii_key.push_str(&format!("\\u{:04x}", unit));
+            }
+        }
+    }
+    format!("urn:li:container:{:x}", md5::compute(ascii_key))
+}
+
+fn proposal(entity_type: &str, urn: &str, aspect_name: &str, aspect: Value) -> Value {
+    json!({
+        "entityType": entity_type,
+        "entityUrn": urn,
+        "changeType": "UPSERT",
+        "aspectName": aspect_name,
+        "aspect": { "json": aspect },
+    })
+}
+
+/// The `com.linkedin.schema` type for a single value of the column.
+fn value_type(field_type: &str) -> &'static str {
+    match field_type {
+        "INTEGER" | "INT64" | "FLOAT" | "FLOAT64" | "NUMERIC" | "DECIMAL" | "BIGNUMERIC"
+        | "BIGDECIMAL" => "NumberType",
+        "BOOLEAN" | "BOOL" => "BooleanType",
+        "DATE" => "DateType",
+        "DATETIME" | "TIMESTAMP" | "TIME" => "TimeType",
+        "BYTES" => "BytesType",
+        "RECORD" | "STRUCT" => "RecordType",
+        _ => "StringType",
+    }
+}
+
+fn native_type(column: &Column) -> String {
+    let element = standard_sql_type(&column.field_type);
+    if column.is_repeated() {
+        format!("ARRAY<{}>", element)
+    } else {
+        element.to_string()
+    }
+}
+
+fn field_type(column: &Column) -> Value {
+    if column.is_repeated() {
+        let nested = standard_sql_type(&column.field_type);
+        json!({ "type": { "com.linkedin.schema.ArrayType": { "nestedType": [nested] } } })
+    } else {
+        let type_name = format!("com.linkedin.schema.{}", value_type(&column.field_type));
+        json!({ "type": { type_name: {} } })
+    }
+}
+
+fn schema_metadata(table: &Table) -> Value {
+    let columns = table
+        .schema
+        .as_ref()
+        .map(|schema| schema.fields.as_slice())
+        .unwrap_or(&[]);
+    let primary_key: Vec<String> = table
+        .table_constraints
+        .as_ref()
+        .and_then(|constraints| constraints.primary_key.as_ref())
+        .map(|primary_key| primary_key.columns.clone())
+        .unwrap_or_default();
+
+    let fields: Vec<Value> = flatten_columns(columns)
+        .into_iter()
+        .map(|flat| {
+            let mut field = json!({
+                "fieldPath": flat.path,
+                "nullable": flat.column.mode() == "NULLABLE",
+                "type": field_type(flat.column),
+                "nativeDataType": native_type(flat.column),
+                "recursive": false,
+                "isPartOfKey": flat.depth == 0 && primary_key.contains(&flat.column.name),
+            });
+            if let Some(description) = &flat.column.description {
+                field["description"] = json!(description);
+            }
+            field
+        })
+        .collect();
+
+    let mut metadata = json!({
+        "schemaName": table.table_reference.to_str(),
+        "platform": PLATFORM,
+        "version": 0,
+        "hash": "",
+        "platformSchema": { "com.linkedin.schema.MySqlDDL": { "tableSchema": "" } },
+        "fields": fields,
+    });
+    if !primary_key.is_empty() {
+        metadata["primaryKeys"] = json!(primary_key);
+    }
+    metadata
+}
+
+fn dataset_properties(table: &Table) -> Value {
+    let reference = &table.table_reference;
+    let mut custom_properties: Map<String, Value> = table
+        .labels
+        .iter()
+        .map(|(key, value)| (format!("label.{}", key), json!(value)))
+        .collect();
+    if let Some(num_rows) = &table.num_rows {
+        custom_properties.insert("num_rows".to_string(), json!(num_rows));
+    }
+    if let Some(partitioning) = &table.time_partitioning {
+        let field = partitioning.field.as_deref().unwrap_or("_PARTITIONTIME");
+        custom_properties.insert("partitioning_column".to_string(), json!(field));
+    }
+    if let Some(partitioning) = &table.range_partitioning {
+        custom_properties.insert("partitioning_column".to_string(), json!(partitioning.field));
+    }
+    if let Some(clustering) = &table.clustering {
+        custom_properties.insert(
+            "clustering_columns".to_string(),
+            json!(clustering.fields.join(",")),
+        );
+    }
+
+    let mut properties = json!({
+        "name": reference.table_id,
+        "qualifiedName": reference.to_str(),
+        "customProperties": custom_properties,
+        "tags": [],
+    });
+    if let Some(description) = &table.description {
+        properties["description"] = json!(description);
+    }
+    properties
+}
+
+impl DatahubRenderer {
+    /// Adds the container for the table's BigQuery dataset the first time one of its tables comes up.
+    fn add_container(&mut self, reference: &TableReference) {
+        let urn = container_urn(reference);
+        if !self.containers.insert(urn.clone()) {
+            return;
+        }
+
+        self.proposals.push(proposal(
+            "container",
+            &urn,
+            "containerProperties",
+            json!({
+                "name": reference.dataset_id,
+                "qualifiedName": format!("{}.{}", reference.project_id, reference.dataset_id),
+                "customProperties": {
+                    "platform": "bigquery",
+                    "env": ENV,
+                    "project_id": reference.project_id,
+                    "dataset_id": reference.dataset_id,
+                },
+            }),
+        ));
+        self.proposals.push(proposal(
+            "container",
+            &urn,
+            "dataPlatformInstance",
+            json!({ "platform": PLATFORM }),
+        ));
+        self.proposals.push(proposal(
+            "container",
+            &urn,
+            "subTypes",
+            json!({ "typeNames": ["Dataset"] }),
+        ));
+    }
+}
+
+impl SchemaRenderer for DatahubRenderer {
+    fn begin(&mut self, _out: &mut dyn Write, _tables: &[Table]) -> Result<()> {
+        self.proposals.clear();
+        self.containers.clear();
+        Ok(())
+    }
+
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        self.add_container(reference);
+
+        let urn = dataset_urn(reference);
+        let sub_type = match table.table_type.as_deref() {
+            Some("VIEW") => "View",
+            Some("MATERIALIZED_VIEW") => "Materialized View",
+            Some("EXTERNAL") => "External Table",
+            Some("SNAPSHOT") => "Snapshot",
+            _ => "Table",
+        };
+
+        self.proposals.push(proposal(
+            "dataset",
+            &urn,
+            "datasetProperties",
+            dataset_properties(table),
+        ));
+        self.proposals.push(proposal(
+            "dataset",
+            &urn,
+            "subTypes",
+            json!({ "typeNames": [sub_type] }),
+        ));
+        self.proposals.push(proposal(
+            "dataset",
+            &urn,
+            "container",
+            json!({ "container": container_urn(reference) }),
+        ));
+        if table.schema.is_some() {
+            self.proposals.push(proposal(
+                "dataset",
+                &urn,
+                "schemaMetadata",
+                schema_metadata(table),
+            ));
+        }
+        if let Some(query) = table.view_query() {
+            self.proposals.push(proposal(
+                "dataset",
+                &urn,
+                "viewProperties",
+                json!({
+                    "materialized": table.materialized_view.is_some(),
+                    "viewLogic": query,
+                    "viewLanguage": "SQL",
+                }),
+            ));
+        }
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        serde_json::to_writer_pretty(&mut *out, &self.proposals)?;
+        self.proposals.clear();
+        writeln!(out)
+    }
+
+    fn extension(&self) -> &'static str {
+        "datahub.json"
+    }
+
+    fn default_file_name(&self) -> String {
+        "datahub_mcps.json".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn container_urns_match_datahub_guids() {
+        // hashlib.md5(json.dumps({"platform": "bigquery", "instance": "PROD", "project_id": "acme",
+        // "dataset_id": "sales"}, separators=(",", ":"), sort_keys=True).encode()).hexdigest()
+        let reference: TableReference = "acme.sales.orders".parse().unwrap();
+        assert_eq!(
+            container_urn(&reference),
+            "urn:li:container:55743849895634e98d426dd292ea036e"
+        );
+        // Non-ASCII characters are hashed as json.dumps escapes them.
+        let reference: TableReference = "acmé.sales.orders".parse().unwrap();
+        assert_eq!(
+            container_urn(&reference),
+            "urn:li:container:4e0c90af7265c3ac30c60959c0126b76"
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! dbt `sources.yml` generation: one source per dataset, with table and column descriptions and data types.
+//!
+//! Nested RECORD fields are listed as dotted column names. When merging into an existing file, sources are matched
+//! by dataset (their `schema`, or `name` when there's no `schema`), tables and columns by name. Only `description`
+//! (when BigQuery has one) and `data_type` are overwritten; tests, meta and any other hand-written keys are kept.
+//! YAML comments in the existing file are not preserved.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::ddl::standard_sql_type;
+use crate::render::{flatten_columns, SchemaRenderer};
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, Result, Write};
+use std::path::Path;
+
+pub struct DbtRenderer {
+    /// The document each `begin` starts from: an empty `version: 2` file or the one being merged into.
+    base: Value,
+    document: Value,
+    /// How many selected projects have each dataset ID, to qualify source names that would otherwise clash.
+    dataset_counts: HashMap<String, usize>,
+}
+
+impl DbtRenderer {
+    pub fn new() -> Self {
+        let mut document = Mapping::new();
+        document.insert("version".into(), 2.into());
+        DbtRenderer {
+            base: Value::Mapping(document),
+            document: Value::Null,
+            dataset_counts: HashMap::new(),
+        }
+    }
+
+    /// Starts from an existing `sources.yml` so hand-written keys survive regeneration.
+    pub fn merging(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let document: Value = serde_yaml::from_str(&contents)
+            .map_err(|err| Error::other(format!("{}: {}", path.display(), err)))?;
+        if !document.is_mapping() {
+            return Err(Error::other(format!(
+                "{}: expected a mapping at the top level",
+                path.display()
+            )));
+        }
+
+        Ok(DbtRenderer {
+            base: document,
+            document: Value::Null,
+            dataset_counts: HashMap::new(),
+        })
+    }
+}
+
+impl Default for DbtRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The sequence under `key`, created if it's missing.
+fn sequence_mut<'a>(value: &'a mut Value, key: &str) -> &'a mut Vec<Value> {
+    let mapping = value.as_mapping_mut().expect("dbt entries are mappings");
+    let entry = mapping
+        .entry(key.into())
+        .or_insert_with(|| Value::Sequence(Vec::new()));
+    if !entry.is_sequence() {
+        *entry = Value::Sequence(Vec::new());
+    }
+    entry.as_sequence_mut().unwrap()
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+/// The entry in `entries` that `matches`, appending a new `{name: ...}` entry if there's none.
+/// The flag is true when the entry was just created.
+fn find_or_insert<'a>(
+    entries: &'a mut Vec<Value>,
+    name: &str,
+    matches: impl Fn(&Value) -> bool,
+) -> (&'a mut Value, bool) {
+    match entries.iter().position(matches) {
+        Some(idx) => (&mut entries[idx], false),
+        None => {
+            let mut entry = Mapping::new();
+            entry.insert("name".into(), name.into());
+            entries.push(Value::Mapping(entry));
+            (entries.last_mut().unwrap(), true)
+        }
+    }
+}
+
+fn set(value: &mut Value, key: &str, new_value: Value) {
+    if let Some(mapping) = value.as_mapping_mut() {
+        mapping.insert(key.into(), new_value);
+    }
+}
+
+fn data_type(column: &Column) -> String {
+    let base = standard_sql_type(&column.field_type);
+    if column.is_repeated() {
+        format!("ARRAY<{}>", base)
+    } else {
+        base.to_string()
+    }
+}
+
+impl SchemaRenderer for DbtRenderer {
+    fn begin(&mut self, _out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.document = self.base.clone();
+
+        let mut projects: HashMap<&str, Vec<&str>> = HashMap::new();
+        for table in tables {
+            let reference = &table.table_reference;
+            let dataset_projects = projects.entry(&reference.dataset_id).or_default();
+            if !dataset_projects.contains(&reference.project_id.as_str()) {
+                dataset_projects.push(&reference.project_id);
+            }
+        }
+        self.dataset_counts = projects
+            .into_iter()
+            .map(|(dataset, projects)| (dataset.to_string(), projects.len()))
+            .collect();
+        Ok(())
+    }
+
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        let source_name = if self.dataset_counts.get(&reference.dataset_id) > Some(&1) {
+            format!("{}_{}", reference.project_id, reference.dataset_id).replace('-', "_")
+        } else {
+            reference.dataset_id.clone()
+        };
+
+        let sources = sequence_mut(&mut self.document, "sources");
+        let (source, created) = find_or_insert(sources, &source_name, |source| {
+            let schema = str_field(source, "schema").or(str_field(source, "name"));
+            let database = str_field(source, "database");
+            schema == Some(reference.dataset_id.as_str())
+                && database.is_none_or(|database| database == reference.project_id)
+        });
+        // Existing sources keep however they were written to point at the dataset.
+        if created {
+            set(source, "database", reference.project_id.as_str().into());
+            set(source, "schema", reference.dataset_id.as_str().into());
+        }
+
+        let tables = sequence_mut(source, "tables");
+        let (table_entry, _) = find_or_insert(tables, &reference.table_id, |entry| {
+            str_field(entry, "name") == Some(reference.table_id.as_str())
+        });
+        if let Some(description) = &table.description {
+            set(table_entry, "description", description.as_str().into());
+        }
+
+        let columns = sequence_mut(table_entry, "columns");
+        let fields = table
+            .schema
+            .as_ref()
+            .map(|schema| schema.fields.as_slice())
+            .unwrap_or(&[]);
+        for flat in flatten_columns(fields) {
+            let (column, _) = find_or_insert(columns, &flat.path, |entry| {
+                str_field(entry, "name") == Some(flat.path.as_str())
+            });
+            if let Some(description) = &flat.column.description {
+                set(column, "description", description.as_str().into());
+            }
+            set(column, "data_type", data_type(flat.column).into());
+        }
+
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        let yaml = serde_yaml::to_string(&self.document).map_err(Error::other)?;
+        write!(out, "{}", yaml)
+    }
+
+    fn extension(&self) -> &'static str {
+        "yml"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    const EXISTING: &str = "\
+version: 2
+sources:
+  - name: raw_sales
+    schema: sales
+    loader: fivetran
+    tables:
+      - name: orders
+        description: Hand-written
+        loaded_at_field: _loaded_at
+        columns:
+          - name: id
+            description: Hand-written id
+            tests:
+              - unique
+              - not_null
+          - name: dropped
+            tests:
+              - not_null
+";
+
+    fn merge(existing: &str) -> Value {
+        let table: Table = serde_json::from_value(json!({
+            "tableReference": { "projectId": "acme", "datasetId": "sales", "tableId": "orders" },
+            "schema": { "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "description": "Line items", "fields": [
+                    { "name": "sku", "type": "STRING" }
+                ] }
+            ] },
+            "description": "One row per order"
+        }))
+        .unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("schema-searcher-dbt-{}.yml", std::process::id()));
+        fs::write(&path, existing).unwrap();
+        let renderer = DbtRenderer::merging(&path);
+        fs::remove_file(&path).unwrap();
+
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut renderer.unwrap(), &[table]).unwrap();
+        serde_yaml::from_slice(&out).unwrap()
+    }
+
+    #[test]
+    fn merge_keeps_hand_written_keys() {
+        let document = merge(EXISTING);
+
+        let sources = document["sources"].as_sequence().unwrap();
+        assert_eq!(sources.len(), 1, "the source is matched by its schema");
+        let source = &sources[0];
+        assert_eq!(source["name"], "raw_sales");
+        assert_eq!(source["loader"], "fivetran");
+        assert!(source.get("database").is_none());
+
+        let table = &source["tables"][0];
+        assert_eq!(table["description"], "One row per order");
+        assert_eq!(table["loaded_at_field"], "_loaded_at");
+
+        let columns = table["columns"].as_sequence().unwrap();
+        let names: Vec<&str> = columns
+            .iter()
+            .map(|column| column["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["id", "dropped", "items", "items.sku"]);
+
+        // No description in BigQuery, so the hand-written one stays.
+        assert_eq!(columns[0]["description"], "Hand-written id");
+        assert_eq!(columns[0]["data_type"], "INT64");
+        assert_eq!(
+            columns[0]["tests"],
+            serde_yaml::from_str::<Value>("[unique, not_null]").unwrap()
+        );
+        // Columns BigQuery no longer has are left for the user to remove.
+        assert_eq!(
+            columns[1]["tests"],
+            serde_yaml::from_str::<Value>("[not_null]").unwrap()
+        );
+        assert_eq!(columns[2]["description"], "Line items");
+        assert_eq!(columns[2]["data_type"], "ARRAY<STRUCT>");
+    }
+
+    #[test]
+    fn merge_adds_sources_for_new_datasets() {
+        let document = merge("version: 2\nsources:\n  - name: ops\n    tables: []\n");
+
+        let sources = document["sources"].as_sequence().unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0]["name"], "ops");
+        assert_eq!(sources[1]["name"], "sales");
+        assert_eq!(sources[1]["database"], "acme");
+        assert_eq!(sources[1]["schema"], "sales");
+    }
+
+    #[test]
+    fn merge_rejects_non_mapping_files() {
+        let path = std::env::temp_dir().join(format!(
+            "schema-searcher-dbt-list-{}.yml",
+            std::process::id()
+        ));
+        fs::write(&path, "- sources\n").unwrap();
+        let result = DbtRenderer::merging(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+    }
+}
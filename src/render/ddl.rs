@@ -0,0 +1,374 @@
+use crate::bigquery::types::{Column, Table, TimePartitioning};
+use crate::render::SchemaRenderer;
+use std::io::{Result, Write};
+
+/// BigQuery DDL: a `CREATE TABLE` per table, or `CREATE VIEW` when the view query is known.
+pub struct DdlRenderer;
+
+/// Maps the legacy type names the API returns (INTEGER, FLOAT, BOOLEAN, RECORD) to standard SQL names.
+pub fn standard_sql_type(field_type: &str) -> &str {
+    match field_type {
+        "INTEGER" => "INT64",
+        "FLOAT" => "FLOAT64",
+        "BOOLEAN" => "BOOL",
+        "RECORD" => "STRUCT",
+        other => other,
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "\\`"))
+}
+
+fn quote_string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("\"{}\"", escaped)
+}
+
+/// The column type including STRUCT fields and the ARRAY wrapper for REPEATED columns.
+fn column_type(column: &Column) -> String {
+    let base = if column.is_record() {
+        let fields: Vec<String> = column.children().iter().map(column_definition).collect();
+        format!("STRUCT<{}>", fields.join(", "))
+    } else {
+        standard_sql_type(&column.field_type).to_string()
+    };
+
+    if column.is_repeated() {
+        format!("ARRAY<{}>", base)
+    } else {
+        base
+    }
+}
+
+fn column_definition(column: &Column) -> String {
+    let mut definition = format!("{} {}", quote_identifier(&column.name), column_type(column));
+    if column.is_required() {
+        definition.push_str(" NOT NULL");
+    }
+    if let Some(description) = &column.description {
+        definition.push_str(&format!(
+            " OPTIONS(description={})",
+            quote_string(description)
+        ));
+    }
+    definition
+}
+
+fn partition_expression(partitioning: &TimePartitioning, table: &Table) -> String {
+    let unit = partitioning.partition_type.as_str();
+    let field = match &partitioning.field {
+        Some(field) => field,
+        None if unit == "DAY" => return "_PARTITIONDATE".to_string(),
+        None => return format!("TIMESTAMP_TRUNC(_PARTITIONTIME, {})", unit),
+    };
+
+    let field_type = table
+        .schema
+        .as_ref()
+        .and_then(|schema| schema.fields.iter().find(|c| &c.name == field))
+        .map(|c| c.field_type.as_str())
+        .unwrap_or("TIMESTAMP");
+    let quoted = quote_identifier(field);
+
+    match (field_type, unit) {
+        ("DATE", "DAY") => quoted,
+        ("DATE", _) => format!("DATE_TRUNC({}, {})", quoted, unit),
+        ("DATETIME", _) => format!("DATETIME_TRUNC({}, {})", quoted, unit),
+        (_, "DAY") => format!("DATE({})", quoted),
+        (_, _) => format!("TIMESTAMP_TRUNC({}, {})", quoted, unit),
+    }
+}
+
+fn table_options(table: &Table) -> Option<String> {
+    let mut options = Vec::new();
+    if let Some(description) = &table.description {
+        options.push(format!("description={}", quote_string(description)));
+    }
+    if !table.labels.is_empty() {
+        let labels: Vec<String> = table
+            .labels
+            .iter()
+            .map(|(key, value)| format!("({}, {})", quote_string(key), quote_string(value)))
+            .collect();
+        options.push(format!("labels=[{}]", labels.join(", ")));
+    }
+    if let Some(expiration_ms) = table
+        .time_partitioning
+        .as_ref()
+        .and_then(|p| p.expiration_ms.as_ref())
+        .and_then(|ms| ms.parse::<f64>().ok())
+    {
+        options.push(format!(
+            "partition_expiration_days={}",
+            expiration_ms / 86_400_000.0
+        ));
+    }
+
+    if options.is_empty() {
+        None
+    } else {
+        Some(format!("OPTIONS(\n  {}\n)", options.join(",\n  ")))
+    }
+}
+
+fn write_view(out: &mut dyn Write, table: &Table, query: &str) -> Result<()> {
+    let name = quote_identifier(&table.table_reference.to_str());
+    if let Some(view) = &table.view {
+        if view.use_legacy_sql == Some(true) {
+            writeln!(
+                out,
+                "-- {} is a legacy SQL view and can't be recreated with standard SQL DDL.",
+                table.table_reference.to_str()
+            )?;
+        }
+        writeln!(out, "CREATE VIEW {}", name)?;
+    } else {
+        writeln!(out, "CREATE MATERIALIZED VIEW {}", name)?;
+    }
+    if let Some(options) = table_options(table) {
+        writeln!(out, "{}", options)?;
+    }
+    writeln!(out, "AS\n{};", query.trim_end().trim_end_matches(';'))
+}
+
+impl SchemaRenderer for DdlRenderer {
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        writeln!(out)?;
+
+        if let Some(query) = table.view_query() {
+            return write_view(out, table, query);
+        }
+
+        let schema = match &table.schema {
+            Some(schema) => schema,
+            None => {
+                return writeln!(
+                    out,
+                    "-- No schema available for {}",
+                    table.table_reference.to_str()
+                )
+            }
+        };
+
+        let mut definitions: Vec<String> = schema.fields.iter().map(column_definition).collect();
+        if let Some(constraints) = &table.table_constraints {
+            if let Some(primary_key) = &constraints.primary_key {
+                let columns: Vec<String> = primary_key
+                    .columns
+                    .iter()
+                    .map(|c| quote_identifier(c))
+                    .collect();
+                definitions.push(format!("PRIMARY KEY ({}) NOT ENFORCED", columns.join(", ")));
+            }
+            for foreign_key in &constraints.foreign_keys {
+                let (referencing, referenced): (Vec<String>, Vec<String>) = foreign_key
+                    .column_references
+                    .iter()
+                    .map(|c| {
+                        (
+                            quote_identifier(&c.referencing_column),
+                            quote_identifier(&c.referenced_column),
+                        )
+                    })
+                    .unzip();
+                let constraint = match &foreign_key.name {
+                    Some(name) => format!("CONSTRAINT {} ", quote_identifier(name)),
+                    None => String::new(),
+                };
+                definitions.push(format!(
+                    "{}FOREIGN KEY ({}) REFERENCES {}({}) NOT ENFORCED",
+                    constraint,
+                    referencing.join(", "),
+                    quote_identifier(&foreign_key.referenced_table.to_str()),
+                    referenced.join(", ")
+                ));
+            }
+        }
+
+        writeln!(
+            out,
+            "CREATE TABLE {} (",
+            quote_identifier(&table.table_reference.to_str())
+        )?;
+        writeln!(out, "  {}", definitions.join(",\n  "))?;
+
+        let mut clauses = vec![")".to_string()];
+        if let Some(partitioning) = &table.time_partitioning {
+            clauses.push(format!(
+                "PARTITION BY {}",
+                partition_expression(partitioning, table)
+            ));
+        }
+        if let Some(partitioning) = &table.range_partitioning {
+            clauses.push(format!(
+                "PARTITION BY RANGE_BUCKET({}, GENERATE_ARRAY({}, {}, {}))",
+                quote_identifier(&partitioning.field),
+                partitioning.range.start,
+                partitioning.range.end,
+                partitioning.range.interval
+            ));
+        }
+        if let Some(clustering) = &table.clustering {
+            let fields: Vec<String> = clustering
+                .fields
+                .iter()
+                .map(|f| quote_identifier(f))
+                .collect();
+            clauses.push(format!("CLUSTER BY {}", fields.join(", ")));
+        }
+        clauses.extend(table_options(table));
+        writeln!(out, "{};", clauses.join("\n"))
+    }
+
+    fn extension(&self) -> &'static str {
+        "sql"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn render(table: serde_json::Value) -> String {
+        let table: Table = serde_json::from_value(table).unwrap();
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut DdlRenderer, &[table]).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn tables_get_columns_partitioning_clustering_and_options() {
+        let output = render(json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "orders" },
+            "schema": { "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+                { "name": "created_at", "type": "TIMESTAMP", "description": "When \"placed\"\nin UTC" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                    { "name": "sku", "type": "STRING", "mode": "REQUIRED" },
+                    { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+                    { "name": "price", "type": "RECORD", "fields": [{ "name": "amount", "type": "NUMERIC" }] }
+                ] }
+            ] },
+            "description": "Orders",
+            "labels": { "team": "sales" },
+            "timePartitioning": { "type": "MONTH", "field": "created_at", "expirationMs": "172800000" },
+            "clustering": { "fields": ["id"] }
+        }));
+
+        assert_eq!(
+            output,
+            "
+CREATE TABLE `p.d.orders` (
+  `id` INT64 NOT NULL,
+  `created_at` TIMESTAMP OPTIONS(description=\"When \\\"placed\\\"\\nin UTC\"),
+  `items` ARRAY<STRUCT<`sku` STRING NOT NULL, `tags` ARRAY<STRING>, `price` STRUCT<`amount` NUMERIC>>>
+)
+PARTITION BY TIMESTAMP_TRUNC(`created_at`, MONTH)
+CLUSTER BY `id`
+OPTIONS(
+  description=\"Orders\",
+  labels=[(\"team\", \"sales\")],
+  partition_expiration_days=2
+);
+"
+        );
+    }
+
+    #[test]
+    fn partition_expressions_follow_the_column_type() {
+        let partition = |field_type: &str, partitioning: serde_json::Value| {
+            let output = render(json!({
+                "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "t" },
+                "schema": { "fields": [{ "name": "day", "type": field_type }] },
+                "timePartitioning": partitioning
+            }));
+            output
+                .lines()
+                .find_map(|line| line.strip_prefix("PARTITION BY "))
+                .and_then(|expression| expression.strip_suffix(';'))
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            partition("DATE", json!({ "type": "DAY", "field": "day" })),
+            "`day`"
+        );
+        assert_eq!(
+            partition("DATE", json!({ "type": "MONTH", "field": "day" })),
+            "DATE_TRUNC(`day`, MONTH)"
+        );
+        assert_eq!(
+            partition("DATETIME", json!({ "type": "HOUR", "field": "day" })),
+            "DATETIME_TRUNC(`day`, HOUR)"
+        );
+        assert_eq!(
+            partition("TIMESTAMP", json!({ "type": "DAY", "field": "day" })),
+            "DATE(`day`)"
+        );
+        assert_eq!(
+            partition("DATE", json!({ "type": "DAY" })),
+            "_PARTITIONDATE"
+        );
+        assert_eq!(
+            partition("DATE", json!({ "type": "HOUR" })),
+            "TIMESTAMP_TRUNC(_PARTITIONTIME, HOUR)"
+        );
+    }
+
+    #[test]
+    fn range_partitioning_and_constraints() {
+        let output = render(json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "t" },
+            "schema": { "fields": [{ "name": "id", "type": "INTEGER" }, { "name": "customer_id", "type": "INTEGER" }] },
+            "rangePartitioning": { "field": "id", "range": { "start": "0", "end": "100", "interval": "10" } },
+            "tableConstraints": {
+                "primaryKey": { "columns": ["id"] },
+                "foreignKeys": [{
+                    "name": "fk_customer",
+                    "referencedTable": { "projectId": "p", "datasetId": "d", "tableId": "customers" },
+                    "columnReferences": [{ "referencingColumn": "customer_id", "referencedColumn": "id" }]
+                }]
+            }
+        }));
+
+        assert!(output.contains("  PRIMARY KEY (`id`) NOT ENFORCED,\n"));
+        assert!(output.contains(
+            "  CONSTRAINT `fk_customer` FOREIGN KEY (`customer_id`) REFERENCES `p.d.customers`(`id`) NOT ENFORCED\n"
+        ));
+        assert!(output.contains("PARTITION BY RANGE_BUCKET(`id`, GENERATE_ARRAY(0, 100, 10));\n"));
+    }
+
+    #[test]
+    fn views_are_created_from_their_query() {
+        let output = render(json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "v" },
+            "type": "VIEW",
+            "description": "Recent orders",
+            "view": { "query": "SELECT * FROM `p.d.orders`;\n" }
+        }));
+        assert_eq!(
+            output,
+            "\nCREATE VIEW `p.d.v`\nOPTIONS(\n  description=\"Recent orders\"\n)\nAS\nSELECT * FROM `p.d.orders`;\n"
+        );
+
+        let output = render(json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "v" },
+            "view": { "query": "SELECT 1", "useLegacySql": true }
+        }));
+        assert!(output.starts_with("\n-- p.d.v is a legacy SQL view"));
+
+        let output = render(json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "mv" },
+            "materializedView": { "query": "SELECT 1" }
+        }));
+        assert!(output.contains("CREATE MATERIALIZED VIEW `p.d.mv`\nAS\nSELECT 1;\n"));
+    }
+}
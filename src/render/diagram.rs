@@ -0,0 +1,436 @@
+//! Entity relationship diagrams as Mermaid `erDiagram` or Graphviz DOT, with each table's top-level columns.
+//!
+//! Edges come from the tables' declared foreign keys. With `--infer-relationships`, columns named `<table>_id` are
+//! also linked to a selected table called `<table>` (or its plural), on that table's `id` or `<table>_id` column.
+//! Inferred edges are drawn dashed.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::SchemaRenderer;
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Diagram {
+    Mermaid,
+    Dot,
+}
+
+struct Relationship {
+    /// The referencing table and columns.
+    from: String,
+    from_columns: Vec<String>,
+    /// The referenced table and columns.
+    to: String,
+    to_columns: Vec<String>,
+    inferred: bool,
+}
+
+pub struct DiagramRenderer {
+    diagram: Diagram,
+    infer_relationships: bool,
+    relationships: Vec<Relationship>,
+    /// Columns that are part of a primary key, keyed by table.
+    primary_keys: HashMap<String, HashSet<String>>,
+    /// Columns that reference another table, keyed by table.
+    foreign_keys: HashMap<String, HashSet<String>>,
+}
+
+impl DiagramRenderer {
+    pub fn new(diagram: Diagram, infer_relationships: bool) -> Self {
+        DiagramRenderer {
+            diagram,
+            infer_relationships,
+            relationships: Vec::new(),
+            primary_keys: HashMap::new(),
+            foreign_keys: HashMap::new(),
+        }
+    }
+}
+
+fn columns(table: &Table) -> &[Column] {
+    table
+        .schema
+        .as_ref()
+        .map(|schema| schema.fields.as_slice())
+        .unwrap_or(&[])
+}
+
+fn declared_relationships(table: &Table, selected: &HashSet<String>) -> Vec<Relationship> {
+    let Some(constraints) = &table.table_constraints else {
+        return Vec::new();
+    };
+
+    constraints
+        .foreign_keys
+        .iter()
+        .filter(|foreign_key| selected.contains(&foreign_key.referenced_table.to_str()))
+        .map(|foreign_key| Relationship {
+            from: table.table_reference.to_str(),
+            from_columns: foreign_key
+                .column_references
+                .iter()
+                .map(|c| c.referencing_column.clone())
+                .collect(),
+            to: foreign_key.referenced_table.to_str(),
+            to_columns: foreign_key
+                .column_references
+                .iter()
+                .map(|c| c.referenced_column.clone())
+                .collect(),
+            inferred: false,
+        })
+        .collect()
+}
+
+/// Matches `<name>_id` columns against selected tables named `<name>`, `<name>s` or `<name>es`.
+fn inferred_relationships(table: &Table, tables: &[Table]) -> Vec<Relationship> {
+    let mut relationships = Vec::new();
+    for column in columns(table) {
+        let name = column.name.to_lowercase();
+        let Some(stem) = name.strip_suffix("_id").filter(|stem| !stem.is_empty()) else {
+            continue;
+        };
+
+        for target in tables {
+            let target_id = target.table_reference.table_id.to_lowercase();
+            let names_match = target_id == stem
+                || target_id == format!("{}s", stem)
+                || target_id == format!("{}es", stem);
+            if !names_match || target.table_reference.to_str() == table.table_reference.to_str() {
+                continue;
+            }
+
+            let target_column = columns(target)
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case("id"))
+                .or_else(|| {
+                    columns(target)
+                        .iter()
+                        .find(|c| c.name.eq_ignore_ascii_case(&column.name))
+                });
+            if let Some(target_column) = target_column {
+                relationships.push(Relationship {
+                    from: table.table_reference.to_str(),
+                    from_columns: vec![column.name.clone()],
+                    to: target.table_reference.to_str(),
+                    to_columns: vec![target_column.name.clone()],
+                    inferred: true,
+                });
+            }
+        }
+    }
+    relationships
+}
+
+/// Mermaid entity names can't contain dots or dashes, so the table reference is shown as an alias.
+fn mermaid_id(reference: &str) -> String {
+    reference
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn mermaid_type(column: &Column) -> String {
+    let field_type: String = column
+        .field_type
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    if column.is_repeated() {
+        format!("{}[]", field_type)
+    } else {
+        field_type
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl SchemaRenderer for DiagramRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.relationships.clear();
+        self.primary_keys.clear();
+        self.foreign_keys.clear();
+
+        let selected: HashSet<String> = tables
+            .iter()
+            .map(|table| table.table_reference.to_str())
+            .collect();
+
+        for table in tables {
+            let reference = table.table_reference.to_str();
+            let mut relationships = declared_relationships(table, &selected);
+            // Declared foreign keys win over guesses on the same columns.
+            if self.infer_relationships {
+                let declared: HashSet<Vec<String>> = relationships
+                    .iter()
+                    .map(|r| r.from_columns.clone())
+                    .collect();
+                relationships.extend(
+                    inferred_relationships(table, tables)
+                        .into_iter()
+                        .filter(|r| !declared.contains(&r.from_columns)),
+                );
+            }
+            for relationship in &relationships {
+                self.foreign_keys
+                    .entry(reference.clone())
+                    .or_default()
+                    .extend(relationship.from_columns.iter().cloned());
+            }
+            self.relationships.extend(relationships);
+
+            if let Some(primary_key) = table
+                .table_constraints
+                .as_ref()
+                .and_then(|c| c.primary_key.as_ref())
+            {
+                self.primary_keys
+                    .insert(reference, primary_key.columns.iter().cloned().collect());
+            }
+        }
+
+        match self.diagram {
+            Diagram::Mermaid => writeln!(out, "erDiagram"),
+            Diagram::Dot => {
+                writeln!(out, "digraph schema {{")?;
+                writeln!(out, "  rankdir=LR;")?;
+                writeln!(out, "  node [shape=plaintext, fontname=\"Helvetica\"];")
+            }
+        }
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = table.table_reference.to_str();
+        let empty = HashSet::new();
+        let primary_key = self.primary_keys.get(&reference).unwrap_or(&empty);
+        let foreign_key = self.foreign_keys.get(&reference).unwrap_or(&empty);
+        let keys = |column: &Column| {
+            let mut keys = Vec::new();
+            if primary_key.contains(&column.name) {
+                keys.push("PK");
+            }
+            if foreign_key.contains(&column.name) {
+                keys.push("FK");
+            }
+            keys
+        };
+
+        match self.diagram {
+            Diagram::Mermaid => {
+                writeln!(out, "    {}[\"{}\"] {{", mermaid_id(&reference), reference)?;
+                for column in columns(table) {
+                    let keys = keys(column);
+                    if keys.is_empty() {
+                        writeln!(out, "        {} {}", mermaid_type(column), column.name)?;
+                    } else {
+                        writeln!(
+                            out,
+                            "        {} {} {}",
+                            mermaid_type(column),
+                            column.name,
+                            keys.join(",")
+                        )?;
+                    }
+                }
+                writeln!(out, "    }}")
+            }
+            Diagram::Dot => {
+                let mut label = String::from(
+                    "<TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\" CELLPADDING=\"4\">",
+                );
+                label.push_str(&format!(
+                    "<TR><TD BGCOLOR=\"lightgrey\"><B>{}</B></TD></TR>",
+                    escape_html(&reference)
+                ));
+                for column in columns(table) {
+                    let mut text = format!("{} {}", column.name, column.field_type);
+                    if column.is_repeated() {
+                        text.push_str("[]");
+                    }
+                    let keys = keys(column);
+                    if !keys.is_empty() {
+                        text.push_str(&format!(" ({})", keys.join(", ")));
+                    }
+                    label.push_str(&format!(
+                        "<TR><TD PORT=\"{}\" ALIGN=\"LEFT\">{}</TD></TR>",
+                        escape_html(&column.name),
+                        escape_html(&text)
+                    ));
+                }
+                label.push_str("</TABLE>");
+                writeln!(out, "  \"{}\" [label=<{}>];", escape_dot(&reference), label)
+            }
+        }
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        for relationship in &self.relationships {
+            match self.diagram {
+                Diagram::Mermaid => {
+                    // Referenced table: exactly one; referencing table: zero or more. Dotted lines are inferred.
+                    let line = if relationship.inferred { ".." } else { "--" };
+                    let mut label = relationship.from_columns.join(", ");
+                    if relationship.inferred {
+                        label.push_str(" (inferred)");
+                    }
+                    writeln!(
+                        out,
+                        "    {} ||{}o{{ {} : \"{}\"",
+                        mermaid_id(&relationship.to),
+                        line,
+                        mermaid_id(&relationship.from),
+                        label.replace('"', "'")
+                    )?;
+                }
+                Diagram::Dot => {
+                    let style = if relationship.inferred {
+                        " [style=dashed]"
+                    } else {
+                        ""
+                    };
+                    for (from_column, to_column) in relationship
+                        .from_columns
+                        .iter()
+                        .zip(&relationship.to_columns)
+                    {
+                        writeln!(
+                            out,
+                            "  \"{}\":\"{}\" -> \"{}\":\"{}\"{};",
+                            escape_dot(&relationship.from),
+                            escape_dot(from_column),
+                            escape_dot(&relationship.to),
+                            escape_dot(to_column),
+                            style
+                        )?;
+                    }
+                }
+            }
+        }
+
+        if self.diagram == Diagram::Dot {
+            writeln!(out, "}}")?;
+        }
+        Ok(())
+    }
+
+    fn extension(&self) -> &'static str {
+        match self.diagram {
+            Diagram::Mermaid => "mmd",
+            Diagram::Dot => "dot",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(table_id: &str, fields: serde_json::Value, constraints: serde_json::Value) -> Table {
+        serde_json::from_value(json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": table_id },
+            "schema": { "fields": fields },
+            "tableConstraints": constraints
+        }))
+        .unwrap()
+    }
+
+    fn tables() -> Vec<Table> {
+        vec![
+            table(
+                "orders",
+                json!([
+                    { "name": "id", "type": "INTEGER" },
+                    { "name": "customer_id", "type": "INTEGER" },
+                    { "name": "address_id", "type": "INTEGER" },
+                    { "name": "tags", "type": "STRING", "mode": "REPEATED" }
+                ]),
+                json!({
+                    "primaryKey": { "columns": ["id"] },
+                    "foreignKeys": [{
+                        "referencedTable": { "projectId": "p", "datasetId": "d", "tableId": "customers" },
+                        "columnReferences": [{ "referencingColumn": "customer_id", "referencedColumn": "id" }]
+                    }, {
+                        "referencedTable": { "projectId": "p", "datasetId": "d", "tableId": "unselected" },
+                        "columnReferences": [{ "referencingColumn": "id", "referencedColumn": "id" }]
+                    }]
+                }),
+            ),
+            table(
+                "customers",
+                json!([{ "name": "id", "type": "INTEGER" }]),
+                json!({ "primaryKey": { "columns": ["id"] } }),
+            ),
+            table(
+                "addresses",
+                json!([{ "name": "address_id", "type": "INTEGER" }]),
+                json!({}),
+            ),
+        ]
+    }
+
+    fn render(diagram: Diagram, infer_relationships: bool) -> String {
+        let mut out = Vec::new();
+        let mut renderer = DiagramRenderer::new(diagram, infer_relationships);
+        write_tables(&mut out, &mut renderer, &tables()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn mermaid_draws_declared_foreign_keys() {
+        assert_eq!(
+            render(Diagram::Mermaid, false),
+            "erDiagram
+    p_d_orders[\"p.d.orders\"] {
+        INTEGER id PK
+        INTEGER customer_id FK
+        INTEGER address_id
+        STRING[] tags
+    }
+    p_d_customers[\"p.d.customers\"] {
+        INTEGER id PK
+    }
+    p_d_addresses[\"p.d.addresses\"] {
+        INTEGER address_id
+    }
+    p_d_customers ||--o{ p_d_orders : \"customer_id\"
+"
+        );
+    }
+
+    #[test]
+    fn inferred_relationships_are_opt_in_and_dotted() {
+        let output = render(Diagram::Mermaid, true);
+
+        assert!(output.contains("        INTEGER address_id FK\n"));
+        assert!(
+            output.contains("    p_d_addresses ||..o{ p_d_orders : \"address_id (inferred)\"\n")
+        );
+        // The declared key on customer_id isn't drawn twice.
+        assert_eq!(output.matches("p_d_customers ||").count(), 1);
+    }
+
+    #[test]
+    fn dot_links_columns_through_ports() {
+        let output = render(Diagram::Dot, true);
+
+        assert!(output.starts_with("digraph schema {\n  rankdir=LR;\n"));
+        assert!(output.contains("<TR><TD PORT=\"tags\" ALIGN=\"LEFT\">tags STRING[]</TD></TR>"));
+        assert!(output.contains("<TD PORT=\"id\" ALIGN=\"LEFT\">id INTEGER (PK)</TD>"));
+        assert!(output.contains("  \"p.d.orders\":\"customer_id\" -> \"p.d.customers\":\"id\";\n"));
+        assert!(output.contains(
+            "  \"p.d.orders\":\"address_id\" -> \"p.d.addresses\":\"address_id\" [style=dashed];\n"
+        ));
+        assert!(output.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,303 @@
+//! A static documentation site: `index.html` with a sidebar of projects, datasets and tables, one section per
+//! table with collapsible RECORD trees, and client-side search over table and column names.
+//!
+//! Everything the page needs is either inlined or a relative `<link>`/`<script src>`, so it works when opened from
+//! the local filesystem without a server.
+
+use crate::bigquery::types::{Column, Table, TableReference};
+use crate::render::{flatten_columns, Layout, SchemaRenderer};
+use serde_json::json;
+use std::io::{Result, Write};
+
+const STYLE: &str = include_str!("style.css");
+const SEARCH: &str = include_str!("search.js");
+
+#[derive(Default)]
+pub struct HtmlRenderer {
+    search_index: Vec<serde_json::Value>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn table_id(reference: &TableReference) -> String {
+    escape(&reference.to_str())
+}
+
+fn write_sidebar(out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+    let mut references: Vec<&TableReference> =
+        tables.iter().map(|table| &table.table_reference).collect();
+    references.sort_by_key(|reference| reference.to_str());
+
+    writeln!(out, "<nav>")?;
+    writeln!(
+        out,
+        "<input id=\"search\" type=\"search\" placeholder=\"Search tables and columns\" autocomplete=\"off\">"
+    )?;
+    writeln!(out, "<ul id=\"results\"></ul>")?;
+    writeln!(out, "<div id=\"tree\">")?;
+
+    let mut idx = 0;
+    while idx < references.len() {
+        let project = &references[idx].project_id;
+        writeln!(out, "<details open><summary>{}</summary>", escape(project))?;
+        while idx < references.len() && &references[idx].project_id == project {
+            let dataset = &references[idx].dataset_id;
+            writeln!(
+                out,
+                "<details open><summary>{}</summary><ul>",
+                escape(dataset)
+            )?;
+            while idx < references.len()
+                && &references[idx].project_id == project
+                && &references[idx].dataset_id == dataset
+            {
+                writeln!(
+                    out,
+                    "<li><a href=\"#{}\">{}</a></li>",
+                    table_id(references[idx]),
+                    escape(&references[idx].table_id)
+                )?;
+                idx += 1;
+            }
+            writeln!(out, "</ul></details>")?;
+        }
+        writeln!(out, "</details>")?;
+    }
+
+    writeln!(out, "</div>")?;
+    writeln!(out, "</nav>")
+}
+
+fn write_columns(out: &mut dyn Write, columns: &[Column], anchor_prefix: &str) -> Result<()> {
+    writeln!(out, "<ul class=\"columns\">")?;
+    for column in columns {
+        let anchor = format!("{}.{}", anchor_prefix, column.name);
+        let label = format!(
+            "<span class=\"name\">{}</span> <span class=\"type\">{}</span> <span class=\"mode\">{}</span>{}",
+            escape(&column.name),
+            escape(&column.field_type),
+            escape(column.mode()),
+            column
+                .description
+                .as_ref()
+                .map(|d| format!("<span class=\"description\">{}</span>", escape(d)))
+                .unwrap_or_default()
+        );
+
+        if column.is_record() {
+            writeln!(
+                out,
+                "<li id=\"{}\"><details open><summary>{}</summary>",
+                escape(&anchor),
+                label
+            )?;
+            write_columns(out, column.children(), &anchor)?;
+            writeln!(out, "</details></li>")?;
+        } else {
+            writeln!(out, "<li id=\"{}\">{}</li>", escape(&anchor), label)?;
+        }
+    }
+    writeln!(out, "</ul>")
+}
+
+impl SchemaRenderer for HtmlRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.search_index.clear();
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html lang=\"en\">")?;
+        writeln!(out, "<head>")?;
+        writeln!(out, "<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>BigQuery schemas</title>")?;
+        writeln!(out, "<link rel=\"stylesheet\" href=\"static/style.css\">")?;
+        writeln!(out, "</head>")?;
+        writeln!(out, "<body>")?;
+        write_sidebar(out, tables)?;
+        writeln!(out, "<main>")?;
+        writeln!(out, "<h1>BigQuery schemas</h1>")?;
+        writeln!(out, "<p class=\"meta\">{} tables</p>", tables.len())
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        let id = reference.to_str();
+
+        writeln!(out, "<section class=\"table\" id=\"{}\">", escape(&id))?;
+        writeln!(out, "<h2>{}</h2>", escape(&id))?;
+        if let Some(description) = &table.description {
+            writeln!(out, "<p>{}</p>", escape(description))?;
+        }
+
+        let mut meta = Vec::new();
+        if let Some(table_type) = &table.table_type {
+            meta.push(escape(table_type));
+        }
+        if let Some(num_rows) = &table.num_rows {
+            meta.push(format!("{} rows", escape(num_rows)));
+        }
+        if let Some(partitioning) = &table.time_partitioning {
+            meta.push(format!(
+                "partitioned by {} ({})",
+                escape(partitioning.field.as_deref().unwrap_or("_PARTITIONTIME")),
+                escape(&partitioning.partition_type)
+            ));
+        }
+        if let Some(clustering) = &table.clustering {
+            meta.push(format!(
+                "clustered by {}",
+                escape(&clustering.fields.join(", "))
+            ));
+        }
+        if !meta.is_empty() {
+            writeln!(out, "<p class=\"meta\">{}</p>", meta.join(" · "))?;
+        }
+
+        let columns = match &table.schema {
+            Some(schema) => {
+                write_columns(out, &schema.fields, &id)?;
+                flatten_columns(&schema.fields)
+                    .into_iter()
+                    .map(|flat| flat.path)
+                    .collect()
+            }
+            None => {
+                writeln!(out, "<p class=\"meta\">No schema available.</p>")?;
+                Vec::new()
+            }
+        };
+        writeln!(out, "</section>")?;
+
+        self.search_index
+            .push(json!({ "name": id, "columns": columns }));
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        writeln!(out, "</main>")?;
+        // Inlined rather than loaded with fetch, which browsers block for file:// pages.
+        let search_index = serde_json::to_string(&self.search_index)?.replace("</", "<\\/");
+        writeln!(out, "<script>var SEARCH_INDEX = {};</script>", search_index)?;
+        writeln!(out, "<script src=\"static/search.js\"></script>")?;
+        writeln!(out, "</body>")?;
+        writeln!(out, "</html>")
+    }
+
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn layout(&self) -> Layout {
+        Layout::Directory {
+            index: "index.html",
+        }
+    }
+
+    fn extra_files(&mut self) -> Vec<(String, String)> {
+        vec![
+            ("static/style.css".to_string(), STYLE.to_string()),
+            ("static/search.js".to_string(), SEARCH.to_string()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::{write_directory, write_tables};
+    use std::fs;
+
+    fn table(reference: &str) -> Table {
+        let mut table = Table::from_reference(reference.parse().unwrap());
+        table.schema = Some(
+            serde_json::from_value(json!({ "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "<b>Key</b> & \"id\"" },
+                { "name": "address", "type": "RECORD", "fields": [
+                    { "name": "city", "type": "STRING" }
+                ] }
+            ] }))
+            .unwrap(),
+        );
+        table
+    }
+
+    fn render(tables: &[Table]) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut HtmlRenderer::default(), tables).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn sidebar_groups_tables_by_project_and_dataset() {
+        let output = render(&[
+            table("p.sales.orders"),
+            table("p.ops.events"),
+            table("p.sales.customers"),
+        ]);
+
+        assert!(output.contains(
+            "<details open><summary>p</summary>
+<details open><summary>ops</summary><ul>
+<li><a href=\"#p.ops.events\">events</a></li>
+</ul></details>
+<details open><summary>sales</summary><ul>
+<li><a href=\"#p.sales.customers\">customers</a></li>
+<li><a href=\"#p.sales.orders\">orders</a></li>
+</ul></details>
+</details>
+"
+        ));
+        assert!(output.contains("<p class=\"meta\">3 tables</p>"));
+    }
+
+    #[test]
+    fn records_are_collapsible_and_text_is_escaped() {
+        let output = render(&[table("p.d.orders")]);
+
+        assert!(output.contains(
+            "<span class=\"description\">&lt;b&gt;Key&lt;/b&gt; &amp; &quot;id&quot;</span>"
+        ));
+        assert!(output.contains(
+            "<li id=\"p.d.orders.address\"><details open><summary><span class=\"name\">address</span>"
+        ));
+        assert!(output.contains("<li id=\"p.d.orders.address.city\">"));
+    }
+
+    #[test]
+    fn search_index_is_inlined() {
+        let mut orders = table("p.d.orders");
+        orders.description = Some("</script> isn't the end".to_string());
+        let output = render(&[orders, table("p.d.x</script>")]);
+
+        assert!(output.contains(
+            "<script>var SEARCH_INDEX = [{\"name\":\"p.d.orders\",\"columns\":[\"id\",\"address\",\"address.city\"]},"
+        ));
+        // Only the page's own script tags close.
+        assert_eq!(output.matches("</script>").count(), 2);
+    }
+
+    #[test]
+    fn static_files_are_written_next_to_the_index() {
+        let dir = std::env::temp_dir().join(format!("schema-searcher-html-{}", std::process::id()));
+        write_directory(
+            &dir,
+            "index.html",
+            &mut HtmlRenderer::default(),
+            &[table("p.d.orders")],
+        )
+        .unwrap();
+        let index = fs::read_to_string(dir.join("index.html")).unwrap();
+        let style = fs::read_to_string(dir.join("static/style.css")).unwrap();
+        let search = fs::read_to_string(dir.join("static/search.js")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(index.contains("<link rel=\"stylesheet\" href=\"static/style.css\">"));
+        assert!(index.contains("<script src=\"static/search.js\"></script>"));
+        assert_eq!(style, STYLE);
+        assert_eq!(search, SEARCH);
+    }
+}
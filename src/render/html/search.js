@@ -0,0 +1,45 @@
+// Client-side search over table and column names. SEARCH_INDEX is inlined in index.html so the site works from
+// file:// without a server.
+(function () {
+  var input = document.getElementById("search");
+  var results = document.getElementById("results");
+  var tree = document.getElementById("tree");
+
+  function entry(href, text) {
+    var li = document.createElement("li");
+    var a = document.createElement("a");
+    a.href = href;
+    a.textContent = text;
+    li.appendChild(a);
+    return li;
+  }
+
+  input.addEventListener("input", function () {
+    var query = input.value.trim().toLowerCase();
+    results.innerHTML = "";
+    tree.classList.toggle("hidden", query.length > 0);
+    if (!query) {
+      return;
+    }
+
+    var shown = 0;
+    SEARCH_INDEX.forEach(function (table) {
+      if (shown >= 200) {
+        return;
+      }
+      if (table.name.toLowerCase().indexOf(query) !== -1) {
+        results.appendChild(entry("#" + table.name, table.name));
+        shown++;
+      }
+      table.columns.forEach(function (column) {
+        if (shown < 200 && column.toLowerCase().indexOf(query) !== -1) {
+          results.appendChild(entry("#" + table.name + "." + column, table.name + " › " + column));
+          shown++;
+        }
+      });
+    });
+    if (shown === 0) {
+      results.appendChild(document.createTextNode("No matches"));
+    }
+  });
+})();
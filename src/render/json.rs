@@ -0,0 +1,220 @@
+//! JSON output.
+//!
+//! A table is serialized with the same field names the BigQuery API uses for `tables.get`:
+//!
+//! ```json
+//! {
+//!   "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "t" },
+//!   "schema": {
+//!     "fields": [
+//!       { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+//!       { "name": "address", "type": "RECORD", "fields": [
+//!         { "name": "city", "type": "STRING", "description": "..." }
+//!       ] }
+//!     ]
+//!   },
+//!   "type": "TABLE",
+//!   "description": "...",
+//!   "labels": { "team": "data" },
+//!   "location": "US",
+//!   "numRows": "42",
+//!   "timePartitioning": { "type": "DAY", "field": "created_at" },
+//!   "clustering": { "fields": ["customer_id"] }
+//! }
+//! ```
+//!
+//! Fields that BigQuery didn't return are left out rather than written as `null`; a missing `mode` means NULLABLE.
+//!
+//! `json` wraps the tables in a single document, `{ "tables": [ ... ] }`. `ndjson` writes one table object per line.
+//! `read_tables` loads either form back into `Table` values.
+
+use crate::bigquery::types::Table;
+use crate::render::SchemaRenderer;
+use serde::Deserialize;
+use std::io::{BufRead, Error, Result, Write};
+
+pub struct JsonRenderer {
+    ndjson: bool,
+    tables_written: usize,
+}
+
+impl JsonRenderer {
+    pub fn document() -> Self {
+        JsonRenderer {
+            ndjson: false,
+            tables_written: 0,
+        }
+    }
+
+    pub fn ndjson() -> Self {
+        JsonRenderer {
+            ndjson: true,
+            tables_written: 0,
+        }
+    }
+}
+
+impl SchemaRenderer for JsonRenderer {
+    fn begin(&mut self, out: &mut dyn Write, _tables: &[Table]) -> Result<()> {
+        self.tables_written = 0;
+        if !self.ndjson {
+            write!(out, "{{\n  \"tables\": [")?;
+        }
+        Ok(())
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        if self.ndjson {
+            let line = serde_json::to_string(table)?;
+            writeln!(out, "{}", line)?;
+        } else {
+            let pretty = serde_json::to_string_pretty(table)?;
+            if self.tables_written > 0 {
+                write!(out, ",")?;
+            }
+            writeln!(out)?;
+            // Indent the table to sit inside the "tables" array.
+            let indented = pretty
+                .lines()
+                .map(|line| format!("    {}", line))
+                .collect::<Vec<String>>()
+                .join("\n");
+            write!(out, "{}", indented)?;
+        }
+
+        self.tables_written += 1;
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        if !self.ndjson {
+            if self.tables_written > 0 {
+                writeln!(out)?;
+                write!(out, "  ")?;
+            }
+            writeln!(out, "]\n}}")?;
+        }
+        Ok(())
+    }
+
+    fn extension(&self) -> &'static str {
+        if self.ndjson {
+            "ndjson"
+        } else {
+            "json"
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TablesDocument {
+    tables: Vec<Table>,
+}
+
+/// Reads tables written by `JsonRenderer`, in either the single document or the NDJSON form. The form is told apart
+/// by whether the input opens with a `{"tables": ...}` object, so errors point into the document as a whole or at
+/// the NDJSON line they're on.
+pub fn read_tables(reader: impl BufRead) -> Result<Vec<Table>> {
+    let mut lines = Vec::new();
+    for line in reader.lines() {
+        lines.push(line?);
+    }
+    let input = lines.join("\n");
+
+    let is_document = input
+        .trim_start()
+        .strip_prefix('{')
+        .is_some_and(|rest| rest.trim_start().starts_with("\"tables\""));
+    if is_document {
+        return serde_json::from_str::<TablesDocument>(&input)
+            .map(|document| document.tables)
+            .map_err(|err| Error::other(format!("invalid tables document: {}", err)));
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str::<Table>(line)
+                .map_err(|err| Error::other(format!("line {}: {}", idx + 1, err)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn tables() -> Vec<Table> {
+        [
+            json!({
+                "tableReference": { "projectId": "acme", "datasetId": "sales", "tableId": "orders" },
+                "schema": { "fields": [
+                    { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+                    { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                        { "name": "sku", "type": "STRING", "description": "Stock keeping unit" }
+                    ] }
+                ] },
+                "type": "TABLE",
+                "description": "One row per order",
+                "labels": { "team": "data" },
+                "location": "EU",
+                "timePartitioning": { "type": "DAY", "field": "created_at" }
+            }),
+            json!({
+                "tableReference": { "projectId": "acme", "datasetId": "sales", "tableId": "customers" },
+                "schema": { "fields": [{ "name": "name", "type": "STRING" }] }
+            }),
+        ]
+        .into_iter()
+        .map(|table| serde_json::from_value(table).unwrap())
+        .collect()
+    }
+
+    fn round_trip(mut renderer: JsonRenderer) {
+        let tables = tables();
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut renderer, &tables).unwrap();
+        let read = read_tables(out.as_slice()).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&read).unwrap(),
+            serde_json::to_value(&tables).unwrap()
+        );
+    }
+
+    #[test]
+    fn document_round_trips() {
+        round_trip(JsonRenderer::document());
+    }
+
+    #[test]
+    fn ndjson_round_trips() {
+        round_trip(JsonRenderer::ndjson());
+    }
+
+    #[test]
+    fn empty_document_round_trips() {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut JsonRenderer::document(), &[]).unwrap();
+        assert!(read_tables(out.as_slice()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn document_errors_point_into_the_document() {
+        let input = "{\n  \"tables\": [\n    { \"tableReference\": 3 }\n  ]\n}\n";
+        let err = read_tables(input.as_bytes()).unwrap_err().to_string();
+        assert!(err.starts_with("invalid tables document: "), "{}", err);
+        assert!(err.contains("line 3"), "{}", err);
+    }
+
+    #[test]
+    fn ndjson_errors_name_the_line() {
+        let input = "{\"tableReference\": {\"projectId\": \"p\", \"datasetId\": \"d\", \"tableId\": \"t\"}}\n\n{\n";
+        let err = read_tables(input.as_bytes()).unwrap_err().to_string();
+        assert!(err.starts_with("line 3: "), "{}", err);
+    }
+}
@@ -0,0 +1,211 @@
+//! JSON Schema (draft 2020-12) output.
+//!
+//! A single table is written as a root schema for one row. Several tables are bundled under `$defs`, keyed by
+//! `project.dataset.table`, so each one can be referenced with `#/$defs/<table>`.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::SchemaRenderer;
+use serde_json::{json, Map, Value};
+use std::io::{Result, Write};
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+#[derive(Default)]
+pub struct JsonSchemaRenderer {
+    // Rendering is deferred to `end` since the output shape depends on how many tables there are.
+    schemas: Vec<(String, Value)>,
+}
+
+/// The schema for a single, non-null value of the column's type.
+fn value_schema(column: &Column) -> Value {
+    if column.is_record() {
+        return object_schema(column.children());
+    }
+
+    match column.field_type.as_str() {
+        "STRING" => json!({ "type": "string" }),
+        "BYTES" => json!({ "type": "string", "contentEncoding": "base64" }),
+        "INTEGER" | "INT64" => json!({ "type": "integer" }),
+        "FLOAT" | "FLOAT64" => json!({ "type": "number" }),
+        // NUMERIC and BIGNUMERIC don't fit in a double, so they travel as decimal strings.
+        "NUMERIC" | "BIGNUMERIC" | "BIGDECIMAL" | "DECIMAL" => json!({
+            "type": "string",
+            "pattern": "^[+-]?([0-9]+(\\.[0-9]*)?|\\.[0-9]+)([eE][+-]?[0-9]+)?$"
+        }),
+        "BOOLEAN" | "BOOL" => json!({ "type": "boolean" }),
+        "TIMESTAMP" => json!({ "type": "string", "format": "date-time" }),
+        "DATE" => json!({ "type": "string", "format": "date" }),
+        // DATETIME and TIME have no time zone, so the RFC 3339 formats don't apply.
+        "DATETIME" => json!({
+            "type": "string",
+            "pattern": "^[0-9]{4}-[0-9]{2}-[0-9]{2}[T ][0-9]{2}:[0-9]{2}:[0-9]{2}(\\.[0-9]{1,6})?$"
+        }),
+        "TIME" => json!({
+            "type": "string",
+            "pattern": "^[0-9]{2}:[0-9]{2}:[0-9]{2}(\\.[0-9]{1,6})?$"
+        }),
+        "JSON" => json!({}),
+        // GEOGRAPHY (WKT), INTERVAL and anything newer are exchanged as strings.
+        _ => json!({ "type": "string" }),
+    }
+}
+
+fn column_schema(column: &Column) -> Value {
+    let mut schema = value_schema(column);
+
+    if column.is_repeated() {
+        schema = json!({ "type": "array", "items": schema });
+    } else if !column.is_required() {
+        if let Some(Value::String(json_type)) = schema.get("type").cloned() {
+            schema["type"] = json!([json_type, "null"]);
+        }
+    }
+
+    if let (Some(description), Value::Object(object)) = (&column.description, &mut schema) {
+        object.insert("description".to_string(), json!(description));
+    }
+    schema
+}
+
+fn object_schema(columns: &[Column]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for column in columns {
+        properties.insert(column.name.clone(), column_schema(column));
+        if column.is_required() {
+            required.push(json!(column.name));
+        }
+    }
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false
+    });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
+}
+
+fn table_schema(table: &Table) -> Value {
+    let columns = table
+        .schema
+        .as_ref()
+        .map(|schema| schema.fields.as_slice())
+        .unwrap_or(&[]);
+
+    let mut schema = json!({ "title": table.table_reference.to_str() });
+    if let Some(description) = &table.description {
+        schema["description"] = json!(description);
+    }
+    if let (Value::Object(schema), Value::Object(object)) = (&mut schema, object_schema(columns)) {
+        schema.extend(object);
+    }
+    schema
+}
+
+impl SchemaRenderer for JsonSchemaRenderer {
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        self.schemas
+            .push((table.table_reference.to_str(), table_schema(table)));
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        let mut document = json!({ "$schema": DIALECT });
+
+        if self.schemas.len() == 1 {
+            let (_, schema) = self.schemas.remove(0);
+            if let (Value::Object(document), Value::Object(schema)) = (&mut document, schema) {
+                document.extend(schema);
+            }
+        } else {
+            let defs: Map<String, Value> = self.schemas.drain(..).collect();
+            document["$defs"] = Value::Object(defs);
+        }
+
+        serde_json::to_writer_pretty(&mut *out, &document)?;
+        writeln!(out)
+    }
+
+    fn extension(&self) -> &'static str {
+        "schema.json"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+
+    fn table(table_id: &str) -> Table {
+        serde_json::from_value(json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": table_id },
+            "description": "Orders",
+            "schema": { "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key" },
+                { "name": "total", "type": "NUMERIC" },
+                { "name": "payload", "type": "JSON" },
+                { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+                { "name": "address", "type": "RECORD", "fields": [
+                    { "name": "city", "type": "STRING", "mode": "REQUIRED" }
+                ] }
+            ] }
+        }))
+        .unwrap()
+    }
+
+    fn render(tables: &[Table]) -> Value {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut JsonSchemaRenderer::default(), tables).unwrap();
+        serde_json::from_slice(&out).unwrap()
+    }
+
+    #[test]
+    fn a_single_table_is_the_root_schema() {
+        let schema = render(&[table("orders")]);
+
+        assert_eq!(schema["$schema"], DIALECT);
+        assert_eq!(schema["title"], "p.d.orders");
+        assert_eq!(schema["description"], "Orders");
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(schema["required"], json!(["id"]));
+
+        let properties = &schema["properties"];
+        assert_eq!(
+            properties["id"],
+            json!({ "type": "integer", "description": "Key" })
+        );
+        assert_eq!(properties["total"]["type"], json!(["string", "null"]));
+        assert!(properties["total"]["pattern"].is_string());
+        // JSON columns accept anything, null included.
+        assert_eq!(properties["payload"], json!({}));
+        assert_eq!(
+            properties["tags"],
+            json!({ "type": "array", "items": { "type": "string" } })
+        );
+        assert_eq!(
+            properties["address"],
+            json!({
+                "type": ["object", "null"],
+                "properties": { "city": { "type": "string" } },
+                "additionalProperties": false,
+                "required": ["city"]
+            })
+        );
+    }
+
+    #[test]
+    fn several_tables_are_bundled_under_defs() {
+        let schema = render(&[table("orders"), table("refunds")]);
+
+        assert_eq!(schema["$schema"], DIALECT);
+        assert!(schema.get("properties").is_none());
+        let defs = schema["$defs"].as_object().unwrap();
+        assert_eq!(defs.len(), 2);
+        assert_eq!(defs["p.d.refunds"]["title"], "p.d.refunds");
+        assert_eq!(defs["p.d.orders"]["required"], json!(["id"]));
+    }
+}
@@ -0,0 +1,566 @@
+//! A compact text format for pasting schemas into LLM prompts, with an optional token budget.
+//!
+//! Tokens are estimated locally (see `estimate_tokens`). When the output doesn't fit in `--max-tokens`, it degrades
+//! one step at a time until it does:
+//!
+//! 1. descriptions are dropped,
+//! 2. RECORDs nested two or more levels deep have their fields collapsed into a field count,
+//! 3. the same for every RECORD inside another RECORD,
+//! 4. the tables are split across several chunk files, each within the budget.
+//!
+//! Every chunk starts with a header saying what was elided to make it fit.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::SchemaRenderer;
+use std::io::{Error, ErrorKind, Result, Write};
+
+/// Approximates a BPE tokenizer: roughly one token per four characters of a word, and one per punctuation mark.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut word_len: usize = 0;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            word_len += 1;
+            continue;
+        }
+        tokens += word_len.div_ceil(4);
+        word_len = 0;
+        if !c.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + word_len.div_ceil(4)
+}
+
+/// How much detail a table is rendered with.
+#[derive(Clone, Copy)]
+struct Detail {
+    descriptions: bool,
+    /// RECORDs at this depth or deeper have their fields collapsed. Top-level columns are depth 0.
+    collapse_depth: Option<usize>,
+}
+
+const DETAIL_STEPS: [Detail; 4] = [
+    Detail {
+        descriptions: true,
+        collapse_depth: None,
+    },
+    Detail {
+        descriptions: false,
+        collapse_depth: None,
+    },
+    Detail {
+        descriptions: false,
+        collapse_depth: Some(2),
+    },
+    Detail {
+        descriptions: false,
+        collapse_depth: Some(1),
+    },
+];
+
+/// A rendered table. `lines[0]` is the heading; the rest can be split across chunks at top-level column boundaries.
+struct Section {
+    lines: Vec<String>,
+    /// Indexes into `lines` where a top-level column starts.
+    breaks: Vec<usize>,
+    /// Line index and dotted path of each RECORD whose fields were collapsed.
+    collapsed: Vec<(usize, String)>,
+    dropped_descriptions: bool,
+}
+
+fn write_columns(
+    columns: &[Column],
+    prefix: &str,
+    depth: usize,
+    detail: Detail,
+    section: &mut Section,
+) {
+    for column in columns {
+        if depth == 0 {
+            section.breaks.push(section.lines.len());
+        }
+        let path = if prefix.is_empty() {
+            column.name.clone()
+        } else {
+            format!("{}.{}", prefix, column.name)
+        };
+        let collapse = column.is_record()
+            && !column.children().is_empty()
+            && detail.collapse_depth.is_some_and(|max| depth >= max);
+
+        let mut line = format!(
+            "{}- {} {}",
+            "  ".repeat(depth),
+            column.name,
+            column.field_type
+        );
+        if column.mode() != "NULLABLE" {
+            line.push(' ');
+            line.push_str(column.mode());
+        }
+        if collapse {
+            line.push_str(&format!(" ({} fields elided)", column.children().len()));
+            section.collapsed.push((section.lines.len(), path.clone()));
+        }
+        if let Some(description) = &column.description {
+            if detail.descriptions {
+                line.push_str(": ");
+                line.push_str(&description.replace('\n', " "));
+            } else {
+                section.dropped_descriptions = true;
+            }
+        }
+        section.lines.push(line);
+
+        if !collapse {
+            write_columns(column.children(), &path, depth + 1, detail, section);
+        }
+    }
+}
+
+fn render_section(table: &Table, detail: Detail) -> Section {
+    let reference = table.table_reference.to_str();
+    let mut section = Section {
+        lines: vec![format!("## {}", reference)],
+        breaks: Vec::new(),
+        collapsed: Vec::new(),
+        dropped_descriptions: false,
+    };
+
+    if let Some(description) = &table.description {
+        if detail.descriptions {
+            section.lines.push(description.replace('\n', " "));
+        } else {
+            section.dropped_descriptions = true;
+        }
+    }
+    if let Some(query) = table.view_query() {
+        section.lines.push(format!(
+            "View: {}",
+            query.split_whitespace().collect::<Vec<_>>().join(" ")
+        ));
+    }
+    let mut facts = Vec::new();
+    if let Some(partitioning) = &table.time_partitioning {
+        facts.push(format!(
+            "Partitioned by {} ({})",
+            partitioning.field.as_deref().unwrap_or("_PARTITIONTIME"),
+            partitioning.partition_type
+        ));
+    }
+    if let Some(partitioning) = &table.range_partitioning {
+        facts.push(format!("Partitioned by {} (RANGE)", partitioning.field));
+    }
+    if let Some(clustering) = &table.clustering {
+        facts.push(format!("Clustered by {}", clustering.fields.join(", ")));
+    }
+    if !facts.is_empty() {
+        section.lines.push(format!("{}.", facts.join(". ")));
+    }
+
+    match &table.schema {
+        Some(schema) => write_columns(&schema.fields, &reference, 0, detail, &mut section),
+        None => section.lines.push("No schema available.".to_string()),
+    }
+    section
+}
+
+pub struct LlmRenderer {
+    max_tokens: Option<usize>,
+    /// Each table rendered at every step of `DETAIL_STEPS`.
+    sections: Vec<Vec<Section>>,
+    /// Chunks after the first, written by `extra_files`.
+    extra_chunks: Vec<String>,
+}
+
+impl LlmRenderer {
+    /// Fails when `max_tokens` can't even fit a chunk's header, since no amount of eliding would help.
+    pub fn new(max_tokens: Option<usize>) -> Result<Self> {
+        let renderer = LlmRenderer {
+            max_tokens,
+            sections: Vec::new(),
+            extra_chunks: Vec::new(),
+        };
+        if let Some(max_tokens) = max_tokens {
+            let header_tokens = estimate_tokens(&renderer.chunk(1, 2, &[]));
+            if max_tokens < header_tokens {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "--max-tokens {} is too small: every chunk starts with a header of about {} tokens",
+                        max_tokens, header_tokens
+                    ),
+                ));
+            }
+        }
+        Ok(renderer)
+    }
+}
+
+const PREAMBLE: &str =
+    "Columns are listed as `- name TYPE MODE: description`. Columns without a mode are NULLABLE, \
+and RECORD fields are indented under their RECORD.";
+
+/// The most collapsed RECORDs named in a chunk header before the rest are only counted.
+const MAX_NAMED_RECORDS: usize = 10;
+
+/// Part of a table that goes into one chunk: `section.lines[start..end]`. Large tables are split at top-level
+/// columns, and the pieces after the first repeat the heading.
+struct Piece<'a> {
+    section: &'a Section,
+    start: usize,
+    end: usize,
+}
+
+impl Piece<'_> {
+    fn text(&self) -> String {
+        let mut text = String::new();
+        if self.start > 0 {
+            text.push_str(&format!("{} (continued)\n", self.section.lines[0]));
+        }
+        for line in &self.section.lines[self.start..self.end] {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+
+    fn collapsed(&self) -> impl Iterator<Item = &str> {
+        self.section
+            .collapsed
+            .iter()
+            .filter(|(line, _)| (self.start..self.end).contains(line))
+            .map(|(_, path)| path.as_str())
+    }
+}
+
+impl LlmRenderer {
+    /// A chunk's text: the header followed by its pieces.
+    fn chunk(&self, index: usize, count: usize, pieces: &[Piece]) -> String {
+        let body: String = pieces
+            .iter()
+            .map(Piece::text)
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let mut notes = String::new();
+        notes.push_str(PREAMBLE);
+        notes.push('\n');
+
+        let mut elided = Vec::new();
+        if pieces
+            .iter()
+            .any(|piece| piece.section.dropped_descriptions)
+        {
+            elided.push("table and column descriptions".to_string());
+        }
+        let collapsed: Vec<&str> = pieces.iter().flat_map(Piece::collapsed).collect();
+        if !collapsed.is_empty() {
+            let mut named = collapsed
+                .iter()
+                .take(MAX_NAMED_RECORDS)
+                .copied()
+                .collect::<Vec<&str>>()
+                .join(", ");
+            if collapsed.len() > MAX_NAMED_RECORDS {
+                named.push_str(&format!(
+                    " and {} more",
+                    collapsed.len() - MAX_NAMED_RECORDS
+                ));
+            }
+            elided.push(format!("fields of the nested RECORDs {}", named));
+        }
+        if let (false, Some(max_tokens)) = (elided.is_empty(), self.max_tokens) {
+            notes.push_str(&format!(
+                "Elided to fit a {} token budget: {}.\n",
+                max_tokens,
+                elided.join("; ")
+            ));
+        }
+        if count > 1 {
+            notes.push_str(&format!(
+                "The tables are split across {} chunks; this is chunk {}.\n",
+                count,
+                index + 1
+            ));
+        }
+
+        let tokens = estimate_tokens(&notes) + estimate_tokens(&body) + 8;
+        let title = if count > 1 {
+            format!(
+                "# BigQuery schemas, chunk {} of {} (~{} tokens)",
+                index + 1,
+                count,
+                tokens
+            )
+        } else {
+            format!("# BigQuery schemas (~{} tokens)", tokens)
+        };
+        format!("{}\n{}\n{}", title, notes, body)
+    }
+
+    fn fits(&self, pieces: &[Piece], budget: usize) -> bool {
+        // The header is estimated as if this were one of many chunks, which is the longest it gets.
+        estimate_tokens(&self.chunk(1, 2, pieces)) <= budget
+    }
+
+    /// Packs sections into as few chunks as fit the budget, keeping table order. A table that doesn't fit in the
+    /// rest of a chunk is split at a top-level column and continued in the next one. A single top-level column too
+    /// big for an empty chunk gets a chunk of its own anyway.
+    fn pack<'a>(&self, sections: &[&'a Section], budget: usize) -> Vec<Vec<Piece<'a>>> {
+        let mut chunks: Vec<Vec<Piece>> = Vec::new();
+        let mut current: Vec<Piece> = Vec::new();
+
+        for section in sections {
+            let mut bounds = section.breaks.clone();
+            bounds.push(section.lines.len());
+            // Tables without columns only have their heading and facts.
+            let first_break = bounds[0];
+
+            let mut piece = Piece {
+                section,
+                start: 0,
+                end: first_break,
+            };
+            let mut has_columns = false;
+            for &bound in &bounds {
+                let previous_end = piece.end;
+                piece.end = bound;
+                current.push(piece);
+                let fits = self.fits(&current, budget);
+                piece = current.pop().unwrap();
+                if fits || (current.is_empty() && !has_columns) {
+                    has_columns |= bound > first_break;
+                    continue;
+                }
+
+                // Close the chunk. Columns already placed stay in it; the table continues in the next chunk.
+                if has_columns {
+                    let continued = Piece {
+                        section,
+                        start: previous_end,
+                        end: bound,
+                    };
+                    piece.end = previous_end;
+                    current.push(std::mem::replace(&mut piece, continued));
+                }
+                chunks.push(std::mem::take(&mut current));
+                has_columns |= bound > first_break;
+            }
+            current.push(piece);
+        }
+
+        if !current.is_empty() {
+            chunks.push(current);
+        }
+        chunks
+    }
+}
+
+impl SchemaRenderer for LlmRenderer {
+    fn begin(&mut self, _out: &mut dyn Write, _tables: &[Table]) -> Result<()> {
+        self.sections.clear();
+        self.extra_chunks.clear();
+        Ok(())
+    }
+
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        self.sections.push(
+            DETAIL_STEPS
+                .iter()
+                .map(|detail| render_section(table, *detail))
+                .collect(),
+        );
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        let budget = self.max_tokens.unwrap_or(usize::MAX);
+
+        for step in 0..DETAIL_STEPS.len() {
+            let pieces: Vec<Piece> = self
+                .sections
+                .iter()
+                .map(|steps| Piece {
+                    section: &steps[step],
+                    start: 0,
+                    end: steps[step].lines.len(),
+                })
+                .collect();
+            let document = self.chunk(0, 1, &pieces);
+            if estimate_tokens(&document) <= budget {
+                return write!(out, "{}", document);
+            }
+        }
+
+        // Even the least detailed rendering is too big, so split it up.
+        let last = DETAIL_STEPS.len() - 1;
+        let sections: Vec<&Section> = self.sections.iter().map(|steps| &steps[last]).collect();
+        let chunks = self.pack(&sections, budget);
+        let mut texts: Vec<String> = chunks
+            .iter()
+            .enumerate()
+            .map(|(idx, pieces)| self.chunk(idx, chunks.len(), pieces))
+            .collect();
+        if texts.is_empty() {
+            return write!(out, "{}", self.chunk(0, 1, &[]));
+        }
+
+        let first = texts.remove(0);
+        self.extra_chunks = texts;
+        write!(out, "{}", first)
+    }
+
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn default_file_name(&self) -> String {
+        "llms.txt".to_string()
+    }
+
+    fn extra_files(&mut self) -> Vec<(String, String)> {
+        self.extra_chunks
+            .drain(..)
+            .enumerate()
+            .map(|(idx, chunk)| (format!("chunk-{}.txt", idx + 2), chunk))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(table_id: &str, columns: usize) -> Table {
+        let fields: Vec<serde_json::Value> = (0..columns)
+            .map(|idx| json!({ "name": format!("column_{}", idx), "type": "STRING", "description": "Some column" }))
+            .collect();
+        serde_json::from_value(json!({
+            "tableReference": { "projectId": "acme", "datasetId": "sales", "tableId": table_id },
+            "schema": { "fields": fields }
+        }))
+        .unwrap()
+    }
+
+    fn sections(tables: &[Table]) -> Vec<Section> {
+        let last = DETAIL_STEPS[DETAIL_STEPS.len() - 1];
+        tables
+            .iter()
+            .map(|table| render_section(table, last))
+            .collect()
+    }
+
+    /// The section lines the packed chunks cover, in order, leaving out the headings continued pieces repeat.
+    fn lines(chunks: &[Vec<Piece>]) -> Vec<String> {
+        let mut lines = Vec::new();
+        for piece in chunks.iter().flatten() {
+            lines.extend_from_slice(&piece.section.lines[piece.start..piece.end]);
+        }
+        lines
+    }
+
+    #[test]
+    fn pack_without_tables_has_no_chunks() {
+        let renderer = LlmRenderer::new(Some(200)).unwrap();
+        assert!(renderer.pack(&[], 200).is_empty());
+    }
+
+    #[test]
+    fn pack_puts_a_small_table_in_one_chunk() {
+        let renderer = LlmRenderer::new(Some(200)).unwrap();
+        let sections = sections(&[table("orders", 3)]);
+        let chunks = renderer.pack(&sections.iter().collect::<Vec<_>>(), 200);
+
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].len(), 1);
+        assert_eq!(
+            (chunks[0][0].start, chunks[0][0].end),
+            (0, sections[0].lines.len())
+        );
+    }
+
+    #[test]
+    fn pack_splits_an_oversized_table_at_columns() {
+        let budget = 150;
+        let renderer = LlmRenderer::new(Some(budget)).unwrap();
+        let sections = sections(&[table("orders", 40), table("customers", 2)]);
+        let section_refs: Vec<&Section> = sections.iter().collect();
+        let chunks = renderer.pack(&section_refs, budget);
+
+        assert!(chunks.len() > 1);
+        for pieces in &chunks {
+            assert!(renderer.fits(pieces, budget));
+        }
+        // Nothing is lost or repeated, and the order is kept.
+        let expected: Vec<String> = sections
+            .iter()
+            .flat_map(|section| section.lines.clone())
+            .collect();
+        assert_eq!(lines(&chunks), expected);
+        // Later pieces of the big table start at a column.
+        for piece in chunks.iter().flatten().filter(|piece| piece.start > 0) {
+            assert!(piece.section.breaks.contains(&piece.start));
+        }
+    }
+
+    #[test]
+    fn oversized_columns_get_a_chunk_of_their_own() {
+        let budget = 100;
+        let renderer = LlmRenderer::new(Some(budget)).unwrap();
+        let mut wide = table("wide", 2);
+        let description = "word ".repeat(200);
+        for column in &mut wide.schema.as_mut().unwrap().fields {
+            column.field_type = "RECORD".to_string();
+            column.fields = Some(vec![serde_json::from_value(
+                json!({ "name": description.trim(), "type": "STRING" }),
+            )
+            .unwrap()]);
+        }
+        let sections = sections(&[wide]);
+        let chunks = renderer.pack(&sections.iter().collect::<Vec<_>>(), budget);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(lines(&chunks), sections[0].lines);
+    }
+
+    #[test]
+    fn end_writes_chunks_within_the_budget() {
+        let mut renderer = LlmRenderer::new(Some(150)).unwrap();
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut renderer, &[table("orders", 40)]).unwrap();
+        let extra = renderer.extra_files();
+
+        assert!(!extra.is_empty());
+        assert_eq!(extra[0].0, "chunk-2.txt");
+        for text in std::iter::once(String::from_utf8(out).unwrap())
+            .chain(extra.into_iter().map(|(_, text)| text))
+        {
+            assert!(estimate_tokens(&text) <= 150, "{}", text);
+            assert!(
+                text.contains("Elided to fit a 150 token budget: table and column descriptions.")
+            );
+        }
+    }
+
+    #[test]
+    fn end_without_tables_writes_the_header() {
+        let mut renderer = LlmRenderer::new(Some(100)).unwrap();
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut renderer, &[]).unwrap();
+
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("# BigQuery schemas"));
+        assert!(renderer.extra_files().is_empty());
+    }
+
+    #[test]
+    fn budgets_below_the_header_are_rejected() {
+        assert!(LlmRenderer::new(Some(10)).is_err());
+        assert!(LlmRenderer::new(None).is_ok());
+    }
+}
@@ -0,0 +1,518 @@
+//! LookML views, one `.view.lkml` file per table.
+//!
+//! Columns become dimensions typed from their BigQuery type, with DATE, DATETIME and TIMESTAMP columns as time
+//! dimension groups. STRUCT fields are flattened into `parent__field` dimensions. Each REPEATED column gets a nested
+//! view named `<view>__<column>` and a hidden dimension in its parent, and the file ends with an explore joining the
+//! nested views with `LEFT JOIN UNNEST(...)`, the same layout Looker generates for nested BigQuery tables. The nested
+//! view of a REPEATED scalar has a single `value` dimension for the element.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::{Layout, SchemaRenderer};
+use std::collections::HashSet;
+use std::io::{Result, Write};
+
+pub struct LookmlRenderer;
+
+/// Suffixes dropped from time dimension group names, which Looker suffixes with the timeframe instead.
+const TIME_SUFFIXES: [&str; 4] = ["_timestamp", "_date", "_time", "_at"];
+
+/// LookML names are lowercase letters, digits and underscores, so `customerId` becomes `customer_id`.
+fn lookml_name(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if previous_lower {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+            previous_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            snake.push(c);
+            previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            snake.push('_');
+            previous_lower = false;
+        }
+    }
+    if snake.is_empty() || snake.starts_with(|c: char| c.is_ascii_digit()) {
+        snake.insert(0, '_');
+    }
+    snake
+}
+
+fn sql_identifier(name: &str) -> String {
+    let plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        name.to_string()
+    } else {
+        format!("`{}`", name.replace('`', "\\`"))
+    }
+}
+
+fn lookml_string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', " ");
+    format!("\"{}\"", escaped)
+}
+
+fn dimension_type(field_type: &str) -> &'static str {
+    match field_type {
+        "INTEGER" | "INT64" | "FLOAT" | "FLOAT64" | "NUMERIC" | "DECIMAL" | "BIGNUMERIC"
+        | "BIGDECIMAL" => "number",
+        "BOOLEAN" | "BOOL" => "yesno",
+        _ => "string",
+    }
+}
+
+struct View {
+    name: String,
+    sql_table_name: Option<String>,
+    /// Table description lines, written as comments since views have no description parameter.
+    comments: Vec<String>,
+    /// Rendered dimension, dimension group and measure blocks.
+    fields: Vec<String>,
+    field_names: HashSet<String>,
+}
+
+impl View {
+    /// An empty view. `count` is taken by the measure every view gets.
+    fn new(name: String) -> Self {
+        View {
+            name,
+            sql_table_name: None,
+            comments: Vec::new(),
+            fields: Vec::new(),
+            field_names: HashSet::from(["count".to_string()]),
+        }
+    }
+
+    /// Reserves `name`, or `name_2`, `name_3`, ... when the view already has a field called that.
+    fn unique_field_name(&mut self, name: String) -> String {
+        let mut candidate = name.clone();
+        let mut suffix = 2;
+        while !self.field_names.insert(candidate.clone()) {
+            candidate = format!("{}_{}", name, suffix);
+            suffix += 1;
+        }
+        candidate
+    }
+
+    /// Like `unique_field_name` for a dimension group, whose fields are `<name>_<timeframe>`. Those have to be free
+    /// as well, so a group `created` can't sit next to a `created_date` dimension.
+    fn unique_group_name(&mut self, name: String, timeframes: &[&str]) -> String {
+        let mut candidate = name.clone();
+        let mut suffix = 2;
+        loop {
+            let names: Vec<String> = std::iter::once(candidate.clone())
+                .chain(
+                    timeframes
+                        .iter()
+                        .map(|timeframe| format!("{}_{}", candidate, timeframe)),
+                )
+                .collect();
+            if names.iter().all(|name| !self.field_names.contains(name)) {
+                self.field_names.extend(names);
+                return candidate;
+            }
+            candidate = format!("{}_{}", name, suffix);
+            suffix += 1;
+        }
+    }
+
+    fn add_field(&mut self, kind: &str, name: &str, parameters: Vec<String>) {
+        let mut block = format!("  {}: {} {{\n", kind, name);
+        for parameter in parameters {
+            block.push_str(&format!("    {}\n", parameter));
+        }
+        block.push_str("  }");
+        self.fields.push(block);
+    }
+}
+
+struct Join {
+    view: String,
+    sql: String,
+}
+
+struct Builder {
+    views: Vec<View>,
+    joins: Vec<Join>,
+    primary_key: Option<String>,
+}
+
+impl Builder {
+    /// Adds `columns` to the view at `view_idx`. `sql_prefix` is how the view reaches them (`${TABLE}` or a STRUCT
+    /// path from it) and `name_prefix`/`group_label` come from enclosing STRUCTs.
+    fn add_columns(
+        &mut self,
+        view_idx: usize,
+        columns: &[Column],
+        sql_prefix: &str,
+        name_prefix: &str,
+        group_label: Option<&str>,
+    ) {
+        for column in columns {
+            let sql = format!("{}.{}", sql_prefix, sql_identifier(&column.name));
+            let name = format!("{}{}", name_prefix, lookml_name(&column.name));
+
+            if column.is_repeated() {
+                self.add_nested_view(view_idx, column, &sql, &name);
+                continue;
+            }
+            if column.is_record() {
+                let label = match group_label {
+                    Some(parent) => format!("{} {}", parent, column.name),
+                    None => column.name.clone(),
+                };
+                self.add_columns(
+                    view_idx,
+                    column.children(),
+                    &sql,
+                    &format!("{}__", name),
+                    Some(&label),
+                );
+                continue;
+            }
+
+            let primary_key = sql_prefix == "${TABLE}"
+                && self.primary_key.as_deref() == Some(column.name.as_str());
+            self.add_dimension(view_idx, column, &sql, name, group_label, primary_key);
+        }
+    }
+
+    fn add_dimension(
+        &mut self,
+        view_idx: usize,
+        column: &Column,
+        sql: &str,
+        name: String,
+        group_label: Option<&str>,
+        primary_key: bool,
+    ) {
+        let view = &mut self.views[view_idx];
+        let mut parameters = Vec::new();
+        if primary_key {
+            parameters.push("primary_key: yes".to_string());
+        }
+
+        let (kind, name) = match column.field_type.as_str() {
+            "DATE" | "DATETIME" | "TIMESTAMP" => {
+                let stripped = TIME_SUFFIXES
+                    .iter()
+                    .find_map(|suffix| name.strip_suffix(suffix))
+                    .filter(|stem| !stem.is_empty() && !stem.ends_with('_'))
+                    .unwrap_or(&name)
+                    .to_string();
+                parameters.push("type: time".to_string());
+                let timeframes: &[&str] = if column.field_type == "DATE" {
+                    &["raw", "date", "week", "month", "quarter", "year"]
+                } else {
+                    &["raw", "time", "date", "week", "month", "quarter", "year"]
+                };
+                parameters.push(format!("timeframes: [{}]", timeframes.join(", ")));
+                match column.field_type.as_str() {
+                    "DATE" => {
+                        parameters.push("convert_tz: no".to_string());
+                        parameters.push("datatype: date".to_string());
+                    }
+                    "DATETIME" => parameters.push("datatype: datetime".to_string()),
+                    _ => {}
+                }
+                (
+                    "dimension_group",
+                    view.unique_group_name(stripped, timeframes),
+                )
+            }
+            field_type => {
+                parameters.push(format!("type: {}", dimension_type(field_type)));
+                ("dimension", view.unique_field_name(name))
+            }
+        };
+
+        if let Some(group_label) = group_label {
+            parameters.push(format!("group_label: {}", lookml_string(group_label)));
+            parameters.push(format!("group_item_label: {}", lookml_string(&column.name)));
+        }
+        if let Some(description) = &column.description {
+            parameters.push(format!("description: {}", lookml_string(description)));
+        }
+        parameters.push(format!("sql: {} ;;", sql));
+        view.add_field(kind, &name, parameters);
+    }
+
+    /// A hidden dimension for the array in the parent view, plus a view for its elements joined with UNNEST.
+    fn add_nested_view(&mut self, parent_idx: usize, column: &Column, sql: &str, name: &str) {
+        let parent = &mut self.views[parent_idx];
+        let hidden = parent.unique_field_name(name.to_string());
+        let mut parameters = vec!["hidden: yes".to_string()];
+        if let Some(description) = &column.description {
+            parameters.push(format!("description: {}", lookml_string(description)));
+        }
+        parameters.push(format!("sql: {} ;;", sql));
+        parent.add_field("dimension", &hidden, parameters);
+
+        let nested_name = format!("{}__{}", parent.name, hidden);
+        self.joins.push(Join {
+            view: nested_name.clone(),
+            sql: format!(
+                "LEFT JOIN UNNEST(${{{}.{}}}) AS {}",
+                parent.name, hidden, nested_name
+            ),
+        });
+
+        self.views.push(View::new(nested_name.clone()));
+        let nested_idx = self.views.len() - 1;
+
+        if column.is_record() {
+            self.add_columns(nested_idx, column.children(), "${TABLE}", "", None);
+        } else {
+            // The element itself is the UNNEST alias.
+            self.add_dimension(
+                nested_idx,
+                column,
+                &nested_name,
+                "value".to_string(),
+                None,
+                false,
+            );
+        }
+    }
+}
+
+fn write_view(out: &mut dyn Write, view: &View) -> Result<()> {
+    writeln!(out)?;
+    for comment in &view.comments {
+        if comment.is_empty() {
+            writeln!(out, "#")?;
+        } else {
+            writeln!(out, "# {}", comment)?;
+        }
+    }
+    writeln!(out, "view: {} {{", view.name)?;
+    if let Some(sql_table_name) = &view.sql_table_name {
+        writeln!(out, "  sql_table_name: {} ;;", sql_table_name)?;
+        writeln!(out)?;
+    }
+    for field in &view.fields {
+        writeln!(out, "{}", field)?;
+        writeln!(out)?;
+    }
+    writeln!(out, "  measure: count {{")?;
+    writeln!(out, "    type: count")?;
+    writeln!(out, "  }}")?;
+    writeln!(out, "}}")
+}
+
+impl SchemaRenderer for LookmlRenderer {
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = table.table_reference.to_str();
+        let view_name = lookml_name(&table.table_reference.table_id);
+
+        let mut view = View::new(view_name.clone());
+        view.sql_table_name = Some(format!("`{}`", reference));
+        view.comments.push(reference.clone());
+        if let Some(description) = &table.description {
+            view.comments
+                .extend(description.lines().map(str::to_string));
+        }
+
+        // Only a single column key maps to LookML's primary_key.
+        let primary_key = table
+            .table_constraints
+            .as_ref()
+            .and_then(|constraints| constraints.primary_key.as_ref())
+            .filter(|primary_key| primary_key.columns.len() == 1)
+            .map(|primary_key| primary_key.columns[0].clone());
+
+        let mut builder = Builder {
+            views: vec![view],
+            joins: Vec::new(),
+            primary_key,
+        };
+        if let Some(schema) = &table.schema {
+            builder.add_columns(0, &schema.fields, "${TABLE}", "", None);
+        }
+
+        for view in &builder.views {
+            write_view(out, view)?;
+        }
+
+        if !builder.joins.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "explore: {} {{", view_name)?;
+            for (idx, join) in builder.joins.iter().enumerate() {
+                if idx > 0 {
+                    writeln!(out)?;
+                }
+                writeln!(out, "  join: {} {{", join.view)?;
+                writeln!(out, "    sql: {} ;;", join.sql)?;
+                writeln!(out, "    relationship: one_to_many")?;
+                writeln!(out, "  }}")?;
+            }
+            writeln!(out, "}}")?;
+        }
+        Ok(())
+    }
+
+    fn extension(&self) -> &'static str {
+        "view.lkml"
+    }
+
+    fn layout(&self) -> Layout {
+        Layout::FilePerTable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(fields: serde_json::Value) -> Table {
+        serde_json::from_value(json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "Orders" },
+            "description": "One row per order",
+            "schema": { "fields": fields },
+            "tableConstraints": { "primaryKey": { "columns": ["id"] } }
+        }))
+        .unwrap()
+    }
+
+    fn render(table: Table) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut LookmlRenderer, &[table]).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn columns_become_typed_dimensions() {
+        let output = render(table(json!([
+            { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+            { "name": "isPaid", "type": "BOOLEAN", "description": "Paid \"in full\"" },
+            { "name": "order_date", "type": "DATE" },
+            { "name": "address", "type": "RECORD", "fields": [
+                { "name": "city", "type": "STRING" }
+            ] }
+        ])));
+
+        assert_eq!(
+            output,
+            "
+# p.d.Orders
+# One row per order
+view: orders {
+  sql_table_name: `p.d.Orders` ;;
+
+  dimension: id {
+    primary_key: yes
+    type: number
+    sql: ${TABLE}.id ;;
+  }
+
+  dimension: is_paid {
+    type: yesno
+    description: \"Paid \\\"in full\\\"\"
+    sql: ${TABLE}.isPaid ;;
+  }
+
+  dimension_group: order {
+    type: time
+    timeframes: [raw, date, week, month, quarter, year]
+    convert_tz: no
+    datatype: date
+    sql: ${TABLE}.order_date ;;
+  }
+
+  dimension: address__city {
+    type: string
+    group_label: \"address\"
+    group_item_label: \"city\"
+    sql: ${TABLE}.address.city ;;
+  }
+
+  measure: count {
+    type: count
+  }
+}
+"
+        );
+    }
+
+    #[test]
+    fn timeframes_do_not_collide_with_dimensions() {
+        let group_first = render(table(json!([
+            { "name": "created_at", "type": "TIMESTAMP" },
+            { "name": "created_date", "type": "STRING" }
+        ])));
+        assert!(group_first.contains("  dimension_group: created {\n"));
+        assert!(group_first.contains("  dimension: created_date_2 {\n"));
+
+        let dimension_first = render(table(json!([
+            { "name": "created_date", "type": "STRING" },
+            { "name": "created_at", "type": "TIMESTAMP" }
+        ])));
+        assert!(dimension_first.contains("  dimension: created_date {\n"));
+        assert!(dimension_first.contains("  dimension_group: created_2 {\n"));
+        assert!(dimension_first
+            .contains("    timeframes: [raw, time, date, week, month, quarter, year]\n"));
+
+        // The `count` measure is taken too.
+        let count = render(table(json!([{ "name": "count", "type": "INTEGER" }])));
+        assert!(count.contains("  dimension: count_2 {\n"));
+    }
+
+    #[test]
+    fn repeated_columns_get_nested_views_and_joins() {
+        let output = render(table(json!([
+            { "name": "items", "type": "RECORD", "mode": "REPEATED", "description": "Lines", "fields": [
+                { "name": "sku", "type": "STRING" },
+                { "name": "tags", "type": "STRING", "mode": "REPEATED" }
+            ] }
+        ])));
+
+        assert!(output.contains(
+            "  dimension: items {
+    hidden: yes
+    description: \"Lines\"
+    sql: ${TABLE}.items ;;
+  }
+"
+        ));
+        assert!(output.contains(
+            "view: orders__items {
+  dimension: sku {
+    type: string
+    sql: ${TABLE}.sku ;;
+  }
+"
+        ));
+        assert!(output.contains(
+            "view: orders__items__tags {
+  dimension: value {
+    type: string
+    sql: orders__items__tags ;;
+  }
+"
+        ));
+        assert!(output.ends_with(
+            "
+explore: orders {
+  join: orders__items {
+    sql: LEFT JOIN UNNEST(${orders.items}) AS orders__items ;;
+    relationship: one_to_many
+  }
+
+  join: orders__items__tags {
+    sql: LEFT JOIN UNNEST(${orders__items.tags}) AS orders__items__tags ;;
+    relationship: one_to_many
+  }
+}
+"
+        ));
+    }
+}
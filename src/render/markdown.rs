@@ -0,0 +1,269 @@
+use crate::bigquery::types::{Table, TableReference};
+use crate::render::{flatten_columns, unique_name, SchemaRenderer};
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+
+/// A Markdown data dictionary: a table of contents grouped by project and dataset, then one section per table
+/// with a pipe table of every column. Nested RECORD fields are listed by their dotted path. Sections are written in
+/// the table of contents' order, sorted by reference.
+#[derive(Default)]
+pub struct MarkdownRenderer {
+    /// Anchor per `project.dataset.table` in the output, so foreign keys only link to sections that exist.
+    anchors: HashMap<String, String>,
+    /// Each table's rendered section, by reference, until `end` writes them in order.
+    sections: Vec<(String, Vec<u8>)>,
+}
+
+const TOC_ANCHOR: &str = "tables";
+
+/// Explicit anchor ids so links don't depend on how a wiki slugifies headings. References that only differ in
+/// punctuation, like `a-b.c.d` and `a.b-c.d`, slugify the same, so `begin` numbers the repeats.
+fn slug(table_reference: &TableReference) -> String {
+    table_reference
+        .to_str()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Keeps descriptions on one line and stops `|` from splitting the cell.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+impl SchemaRenderer for MarkdownRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        let mut references: Vec<&TableReference> =
+            tables.iter().map(|table| &table.table_reference).collect();
+        references.sort_by_key(|reference| reference.to_str());
+        references.dedup_by_key(|reference| reference.to_str());
+
+        let mut taken = HashSet::from([TOC_ANCHOR.to_string()]);
+        self.anchors = references
+            .iter()
+            .map(|reference| {
+                let anchor = unique_name(&mut taken, &slug(reference), "-");
+                (reference.to_str(), anchor)
+            })
+            .collect();
+        self.sections.clear();
+
+        writeln!(out, "# BigQuery data dictionary\n")?;
+        writeln!(out, "<a id=\"{}\"></a>\n", TOC_ANCHOR)?;
+
+        let mut project = None;
+        let mut dataset = None;
+        for reference in references {
+            if project != Some(&reference.project_id) {
+                project = Some(&reference.project_id);
+                dataset = None;
+                writeln!(out, "- **{}**", reference.project_id)?;
+            }
+            if dataset != Some(&reference.dataset_id) {
+                dataset = Some(&reference.dataset_id);
+                writeln!(out, "  - **{}**", reference.dataset_id)?;
+            }
+            writeln!(
+                out,
+                "    - [{}](#{})",
+                reference.table_id,
+                self.anchors[&reference.to_str()]
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        let mut section = Vec::new();
+        self.render_section(&mut section, table)?;
+        self.sections
+            .push((table.table_reference.to_str(), section));
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        self.sections
+            .sort_by(|(left, _), (right, _)| left.cmp(right));
+        for (_, section) in self.sections.drain(..) {
+            out.write_all(&section)?;
+        }
+        Ok(())
+    }
+
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+}
+
+impl MarkdownRenderer {
+    fn render_section(&self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        let anchor = self
+            .anchors
+            .get(&reference.to_str())
+            .cloned()
+            .unwrap_or_else(|| slug(reference));
+        writeln!(out, "\n<a id=\"{}\"></a>\n", anchor)?;
+        writeln!(out, "## {}\n", reference.to_str())?;
+
+        if let Some(description) = &table.description {
+            writeln!(out, "{}\n", description)?;
+        }
+        if let Some(table_type) = &table.table_type {
+            writeln!(out, "- Type: {}", table_type)?;
+        }
+        if let Some(partitioning) = &table.time_partitioning {
+            writeln!(
+                out,
+                "- Partitioned by: {} ({})",
+                partitioning.field.as_deref().unwrap_or("_PARTITIONTIME"),
+                partitioning.partition_type
+            )?;
+        }
+        if let Some(partitioning) = &table.range_partitioning {
+            writeln!(out, "- Partitioned by: {} (RANGE)", partitioning.field)?;
+        }
+        if let Some(clustering) = &table.clustering {
+            writeln!(out, "- Clustered by: {}", clustering.fields.join(", "))?;
+        }
+        if let Some(constraints) = &table.table_constraints {
+            if let Some(primary_key) = &constraints.primary_key {
+                writeln!(out, "- Primary key: {}", primary_key.columns.join(", "))?;
+            }
+            for foreign_key in &constraints.foreign_keys {
+                let referencing: Vec<&str> = foreign_key
+                    .column_references
+                    .iter()
+                    .map(|c| c.referencing_column.as_str())
+                    .collect();
+                let referenced = foreign_key.referenced_table.to_str();
+                if let Some(referenced_anchor) = self.anchors.get(&referenced) {
+                    writeln!(
+                        out,
+                        "- Foreign key: {} → [{}](#{})",
+                        referencing.join(", "),
+                        referenced,
+                        referenced_anchor
+                    )?;
+                } else {
+                    writeln!(
+                        out,
+                        "- Foreign key: {} → {}",
+                        referencing.join(", "),
+                        referenced
+                    )?;
+                }
+            }
+        }
+
+        match &table.schema {
+            Some(schema) => {
+                writeln!(out, "\n| Column | Type | Mode | Description |")?;
+                writeln!(out, "| --- | --- | --- | --- |")?;
+                for flat in flatten_columns(&schema.fields) {
+                    writeln!(
+                        out,
+                        "| `{}` | {} | {} | {} |",
+                        flat.path,
+                        flat.column.field_type,
+                        flat.column.mode(),
+                        escape_cell(flat.column.description.as_deref().unwrap_or(""))
+                    )?;
+                }
+            }
+            None => writeln!(out, "\nNo schema available.")?,
+        }
+
+        writeln!(out, "\n[Back to tables](#{})", TOC_ANCHOR)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(reference: &str, extra: serde_json::Value) -> Table {
+        let mut table = Table::from_reference(reference.parse().unwrap());
+        table.schema = Some(
+            serde_json::from_value(json!({ "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key | id\nsecond line" },
+                { "name": "address", "type": "RECORD", "fields": [{ "name": "city", "type": "STRING" }] }
+            ] }))
+            .unwrap(),
+        );
+        if let Some(constraints) = extra.get("tableConstraints") {
+            table.table_constraints = Some(serde_json::from_value(constraints.clone()).unwrap());
+        }
+        table
+    }
+
+    fn render(tables: &[Table]) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut MarkdownRenderer::default(), tables).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn anchors_are_unique() {
+        let output = render(&[table("a-b.c.d", json!({})), table("a.b-c.d", json!({}))]);
+
+        assert!(output.contains("    - [d](#a-b-c-d)\n"));
+        assert!(output.contains("    - [d](#a-b-c-d-2)\n"));
+        assert!(output.contains("<a id=\"a-b-c-d\"></a>\n\n## a-b.c.d\n"));
+        assert!(output.contains("<a id=\"a-b-c-d-2\"></a>\n\n## a.b-c.d\n"));
+    }
+
+    #[test]
+    fn sections_follow_the_table_of_contents() {
+        let output = render(&[
+            table("p.sales.orders", json!({})),
+            table("p.ops.events", json!({})),
+            table("p.sales.customers", json!({})),
+        ]);
+
+        let position = |text: &str| output.find(text).unwrap();
+        assert!(position("[events]") < position("[customers]"));
+        assert!(position("[customers]") < position("[orders]"));
+        assert!(position("## p.ops.events") < position("## p.sales.customers"));
+        assert!(position("## p.sales.customers") < position("## p.sales.orders"));
+    }
+
+    #[test]
+    fn columns_are_listed_by_path_with_escaped_descriptions() {
+        let output = render(&[table("p.d.t", json!({}))]);
+
+        assert!(output.contains("| `id` | INTEGER | REQUIRED | Key \\| id<br>second line |\n"));
+        assert!(output.contains("| `address.city` | STRING | NULLABLE |  |\n"));
+    }
+
+    #[test]
+    fn foreign_keys_link_to_selected_tables_only() {
+        let foreign_keys = |table_id: &str| {
+            json!({ "tableConstraints": { "foreignKeys": [{
+                "referencedTable": { "projectId": "p", "datasetId": "d", "tableId": table_id },
+                "columnReferences": [{ "referencingColumn": "id", "referencedColumn": "id" }]
+            }] } })
+        };
+        let output = render(&[
+            table("p.d.orders", foreign_keys("customers")),
+            table("p.d.customers", json!({})),
+            table("p.d.events", foreign_keys("users")),
+        ]);
+
+        assert!(output.contains("- Foreign key: id → [p.d.customers](#p-d-customers)\n"));
+        assert!(output.contains("- Foreign key: id → p.d.users\n"));
+    }
+}
@@ -0,0 +1,395 @@
+//! Output formats. Each format implements `SchemaRenderer` and is picked from the CLI with `--format`.
+
+pub mod arrow;
+pub mod avro;
+pub mod bq_schema;
+pub mod catalog;
+pub mod datahub;
+pub mod dbt;
+pub mod ddl;
+pub mod diagram;
+pub mod html;
+pub mod json;
+pub mod json_schema;
+pub mod llm;
+pub mod lookml;
+pub mod markdown;
+pub mod odcs;
+pub mod parquet;
+pub mod proto;
+pub mod python;
+pub mod query;
+pub mod rust;
+pub mod spark;
+pub mod template;
+pub mod terraform;
+pub mod text;
+pub mod typescript;
+
+use crate::bigquery::types::{Column, Table};
+use clap::ValueEnum;
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+use std::path::PathBuf;
+
+/// Turns a list of tables into one output document.
+///
+/// `io::writer::write_tables` drives a renderer by calling `begin` once, `render_table` for every
+/// table, then `end` once, so structured formats can open and close a document around the tables. The same renderer
+/// can be driven through several documents in a row, one per table when writing a file tree, so `begin` must drop
+/// anything buffered for a previous document.
+pub trait SchemaRenderer {
+    /// Written before the first table. Receives every table so headers like a table of contents can be built.
+    fn begin(&mut self, _out: &mut dyn Write, _tables: &[Table]) -> Result<()> {
+        Ok(())
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()>;
+
+    /// Written after the last table.
+    fn end(&mut self, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+
+    /// File extension (without the dot) used for default output file names.
+    fn extension(&self) -> &'static str;
+
+    /// The output file name suggested when none is given.
+    fn default_file_name(&self) -> String {
+        format!("tables.{}", self.extension())
+    }
+
+    /// How the output is laid out on disk, see `Layout`.
+    fn layout(&self) -> Layout {
+        Layout::File
+    }
+
+    /// Files written next to the main document, as (relative path, contents). Called after `end`.
+    ///
+    /// In `Layout::Directory` the paths are relative to the directory. In `Layout::File` they're placed next to the
+    /// output file and prefixed with its stem, so `chunk-2.txt` next to `llms.txt` becomes `llms.chunk-2.txt`.
+    fn extra_files(&mut self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Files written once into each `<project>/<dataset>` directory by `io::writer::write_table_tree`, before the
+    /// documents of that dataset's tables, as (file name, contents). For declarations every table needs but that
+    /// mustn't be repeated, like Terraform's dataset resources. A renderer returning any leaves those declarations
+    /// out of the documents that follow.
+    fn dataset_files(&mut self, _tables: &[&Table]) -> Result<Vec<(String, String)>> {
+        Ok(Vec::new())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// One document holding every table.
+    File,
+    /// A directory with one document per table, for formats like bq schema files that only hold one table.
+    FilePerTable,
+    /// A directory holding the document under the given file name plus the renderer's `extra_files`.
+    Directory { index: &'static str },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Indented `|- name (TYPE) [MODE]` tree.
+    Text,
+    /// One JSON document holding every table, see `render::json`.
+    Json,
+    /// One JSON table object per line.
+    Ndjson,
+    /// Markdown data dictionary with a table of contents.
+    Markdown,
+    /// BigQuery `CREATE TABLE` / `CREATE VIEW` statements.
+    Ddl,
+    /// One `bq`-compatible JSON schema file per table.
+    BqSchema,
+    /// JSON Schema (draft 2020-12) describing one row of each table.
+    JsonSchema,
+    /// Avro `.avsc` record schemas, matching BigQuery's Avro export.
+    Avro,
+    /// proto2 messages for the Storage Write API.
+    Proto,
+    /// dbt `sources.yml` with one source per dataset.
+    Dbt,
+    /// `serde`-derivable Rust structs.
+    Rust,
+    /// TypeScript interfaces.
+    Typescript,
+    /// Python `dataclasses`.
+    PythonDataclass,
+    /// Python `TypedDict`s.
+    PythonTypeddict,
+    /// Arrow schema in its JSON representation, with the Storage Read API's types.
+    Arrow,
+    /// Parquet message type schema.
+    Parquet,
+    /// A static documentation site with search, written to a directory.
+    Html,
+    /// Compact text for LLM prompts, with an optional token budget.
+    Llm,
+    /// Mermaid `erDiagram` of the tables and their relationships.
+    Mermaid,
+    /// Graphviz DOT diagram of the tables and their relationships.
+    Dot,
+    /// One CSV row per column, nested fields included.
+    Csv,
+    /// One tab-separated row per column, nested fields included.
+    Tsv,
+    /// A user-supplied Jinja-style template, see `render::template`.
+    Template,
+    /// A starter `SELECT` per table over every leaf column, unnesting REPEATED fields.
+    Query,
+    /// Terraform `google_bigquery_dataset` and `google_bigquery_table` resources with import blocks.
+    Terraform,
+    /// Spark `StructType` definitions as Scala code.
+    SparkScala,
+    /// Spark schemas in the `df.schema.json()` format.
+    SparkJson,
+    /// One LookML `.view.lkml` file per table.
+    Lookml,
+    /// An Open Data Contract Standard (ODCS) YAML contract for the tables.
+    Odcs,
+    /// DataHub metadata change proposals for file-based ingestion.
+    Datahub,
+}
+
+/// Format-specific settings from the CLI. Formats ignore options that aren't theirs.
+#[derive(Debug, Default, Clone)]
+pub struct RenderOptions {
+    /// dbt: an existing `sources.yml` to merge the generated sources into.
+    pub dbt_merge: Option<PathBuf>,
+    /// llm: the token budget per output file.
+    pub max_tokens: Option<usize>,
+    /// mermaid, dot: also guess relationships from `<table>_id` column names.
+    pub infer_relationships: bool,
+    /// template: the template file. The built-in text template is used when it's missing.
+    pub template: Option<PathBuf>,
+}
+
+impl Format {
+    /// Builds the renderer for this format. Any input files named in `options` are read here, before the output
+    /// file is created, so a file can be merged into itself.
+    pub fn renderer(self, options: &RenderOptions) -> Result<Box<dyn SchemaRenderer>> {
+        Ok(match self {
+            Format::Text => Box::new(text::TextRenderer),
+            Format::Json => Box::new(json::JsonRenderer::document()),
+            Format::Ndjson => Box::new(json::JsonRenderer::ndjson()),
+            Format::Markdown => Box::new(markdown::MarkdownRenderer::default()),
+            Format::Ddl => Box::new(ddl::DdlRenderer),
+            Format::BqSchema => Box::new(bq_schema::BqSchemaRenderer),
+            Format::JsonSchema => Box::new(json_schema::JsonSchemaRenderer::default()),
+            Format::Avro => Box::new(avro::AvroRenderer::default()),
+            Format::Proto => Box::new(proto::ProtoRenderer::default()),
+            Format::Dbt => match &options.dbt_merge {
+                Some(path) => Box::new(dbt::DbtRenderer::merging(path)?),
+                None => Box::new(dbt::DbtRenderer::new()),
+            },
+            Format::Rust => Box::new(rust::RustRenderer::default()),
+            Format::Typescript => Box::new(typescript::TypeScriptRenderer::default()),
+            Format::PythonDataclass => Box::new(python::PythonRenderer::dataclasses()),
+            Format::PythonTypeddict => Box::new(python::PythonRenderer::typed_dicts()),
+            Format::Arrow => Box::new(arrow::ArrowRenderer::default()),
+            Format::Parquet => Box::new(parquet::ParquetRenderer),
+            Format::Html => Box::new(html::HtmlRenderer::default()),
+            Format::Llm => Box::new(llm::LlmRenderer::new(options.max_tokens)?),
+            Format::Mermaid => Box::new(diagram::DiagramRenderer::new(
+                diagram::Diagram::Mermaid,
+                options.infer_relationships,
+            )),
+            Format::Dot => Box::new(diagram::DiagramRenderer::new(
+                diagram::Diagram::Dot,
+                options.infer_relationships,
+            )),
+            Format::Csv => Box::new(catalog::CatalogRenderer::csv()),
+            Format::Tsv => Box::new(catalog::CatalogRenderer::tsv()),
+            Format::Template => match &options.template {
+                Some(path) => Box::new(template::TemplateRenderer::from_file(path)?),
+                None => Box::new(template::TemplateRenderer::text()),
+            },
+            Format::Query => Box::new(query::QueryRenderer),
+            Format::Terraform => Box::new(terraform::TerraformRenderer::default()),
+            Format::SparkScala => Box::new(spark::SparkRenderer::scala()),
+            Format::SparkJson => Box::new(spark::SparkRenderer::json()),
+            Format::Lookml => Box::new(lookml::LookmlRenderer),
+            Format::Odcs => Box::new(odcs::OdcsRenderer::default()),
+            Format::Datahub => Box::new(datahub::DatahubRenderer::default()),
+        })
+    }
+}
+
+/// A column with its position in the nested RECORD tree.
+pub struct FlatColumn<'a> {
+    /// Dotted path from the top-level column, e.g. `items.discounts.code`.
+    pub path: String,
+    pub column: &'a Column,
+    /// 0 for top-level columns.
+    pub depth: usize,
+    /// True when any RECORD above this column is REPEATED.
+    pub repeated_ancestor: bool,
+}
+
+/// Walks the column tree depth first, listing every column (RECORDs included) before its nested fields.
+pub fn flatten_columns(columns: &[Column]) -> Vec<FlatColumn<'_>> {
+    fn walk<'a>(
+        columns: &'a [Column],
+        prefix: &str,
+        depth: usize,
+        repeated_ancestor: bool,
+        flat: &mut Vec<FlatColumn<'a>>,
+    ) {
+        for column in columns {
+            let path = if prefix.is_empty() {
+                column.name.clone()
+            } else {
+                format!("{}.{}", prefix, column.name)
+            };
+            flat.push(FlatColumn {
+                path: path.clone(),
+                column,
+                depth,
+                repeated_ancestor,
+            });
+            walk(
+                column.children(),
+                &path,
+                depth + 1,
+                repeated_ancestor || column.is_repeated(),
+                flat,
+            );
+        }
+    }
+
+    let mut flat = Vec::new();
+    walk(columns, "", 0, false, &mut flat);
+    flat
+}
+
+/// `line_items` -> `LineItems`, for type names in generated code.
+pub fn pascal_case(name: &str) -> String {
+    let mut pascal = String::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            pascal.push(first.to_ascii_uppercase());
+            pascal.extend(chars);
+        }
+    }
+    if pascal.is_empty() || pascal.starts_with(|c: char| c.is_ascii_digit()) {
+        pascal.insert(0, '_');
+    }
+    pascal
+}
+
+/// Reserves `name` in `taken`, or `name{separator}2`, `name{separator}3`, ... when it's already taken.
+pub fn unique_name(taken: &mut HashSet<String>, name: &str, separator: &str) -> String {
+    let mut candidate = name.to_string();
+    let mut suffix = 2;
+    while !taken.insert(candidate.clone()) {
+        candidate = format!("{}{}{}", name, separator, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+/// Each table's reference and ID, in the order of `tables`. The ID is qualified as `dataset_table` when two selected
+/// tables share it, and as `project_dataset_table` when they also share the dataset ID.
+pub fn qualified_table_ids(tables: &[Table]) -> Vec<(String, String)> {
+    let mut table_id_counts: HashMap<&str, usize> = HashMap::new();
+    let mut dataset_table_counts: HashMap<(&str, &str), usize> = HashMap::new();
+    for table in tables {
+        let reference = &table.table_reference;
+        *table_id_counts.entry(&reference.table_id).or_default() += 1;
+        *dataset_table_counts
+            .entry((&reference.dataset_id, &reference.table_id))
+            .or_default() += 1;
+    }
+
+    tables
+        .iter()
+        .map(|table| {
+            let reference = &table.table_reference;
+            let id = if table_id_counts[reference.table_id.as_str()] == 1 {
+                reference.table_id.clone()
+            } else if dataset_table_counts
+                [&(reference.dataset_id.as_str(), reference.table_id.as_str())]
+                == 1
+            {
+                format!("{}_{}", reference.dataset_id, reference.table_id)
+            } else {
+                format!(
+                    "{}_{}_{}",
+                    reference.project_id, reference.dataset_id, reference.table_id
+                )
+            };
+            (reference.to_str(), id)
+        })
+        .collect()
+}
+
+/// A type name per table, keyed by `project.dataset.table`: the PascalCased `qualified_table_ids`. A number is added
+/// to names that still clash once PascalCased, like `order_items` and `orderItems`, and to names in `reserved`, the
+/// types the generated code refers to by name.
+pub fn table_type_names(tables: &[Table], reserved: &[&str]) -> HashMap<String, String> {
+    let mut taken: HashSet<String> = reserved.iter().map(|name| name.to_string()).collect();
+    qualified_table_ids(tables)
+        .into_iter()
+        .map(|(reference, id)| {
+            let name = unique_name(&mut taken, &pascal_case(&id), "");
+            (reference, name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(references: &[&str]) -> Vec<Table> {
+        references
+            .iter()
+            .map(|reference| Table::from_reference(reference.parse().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn type_names_are_qualified_only_as_far_as_needed() {
+        let names = table_type_names(
+            &tables(&[
+                "p1.sales.orders",
+                "p2.sales.orders",
+                "p1.ops.orders",
+                "p1.sales.order_items",
+                "p1.sales.orderItems",
+                "p1.sales.customers",
+                "p1.sales.option",
+            ]),
+            &["Option"],
+        );
+        assert_eq!(names["p1.sales.orders"], "P1SalesOrders");
+        assert_eq!(names["p2.sales.orders"], "P2SalesOrders");
+        assert_eq!(names["p1.ops.orders"], "OpsOrders");
+        assert_eq!(names["p1.sales.order_items"], "OrderItems");
+        assert_eq!(names["p1.sales.orderItems"], "OrderItems2");
+        assert_eq!(names["p1.sales.customers"], "Customers");
+        assert_eq!(names["p1.sales.option"], "Option2");
+    }
+
+    #[test]
+    fn pascal_case_makes_identifiers() {
+        assert_eq!(pascal_case("order_items"), "OrderItems");
+        assert_eq!(pascal_case("web-events.v2"), "WebEventsV2");
+        assert_eq!(pascal_case("2024_orders"), "_2024Orders");
+        assert_eq!(pascal_case("__"), "_");
+    }
+
+    #[test]
+    fn unique_name_numbers_repeats() {
+        let mut taken = HashSet::new();
+        assert_eq!(unique_name(&mut taken, "id", "_"), "id");
+        assert_eq!(unique_name(&mut taken, "id", "_"), "id_2");
+        assert_eq!(unique_name(&mut taken, "id", "_"), "id_3");
+        assert_eq!(unique_name(&mut taken, "id_2", "_"), "id_2_2");
+    }
+}
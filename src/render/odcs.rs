@@ -0,0 +1,334 @@
+//! Open Data Contract Standard (ODCS) v3 contracts, see `contract`: one schema object per table, with a property
+//! per column. Nested fields are `object` properties and REPEATED columns `array` properties with the element under
+//! `items`. Physical types use the standard SQL names, and each table's `physicalName` is its full reference so
+//! `--verify` can find it. Schema objects are named after the table, qualified as far as `qualified_table_ids`
+//! needs to keep the names unique.
+
+use crate::bigquery::types::{Column, Table};
+use crate::contract::{DataContract, SchemaObject, SchemaProperty, Server, API_VERSION};
+use crate::render::ddl::standard_sql_type;
+use crate::render::{qualified_table_ids, unique_name, SchemaRenderer};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{Error, Result, Write};
+
+#[derive(Default)]
+pub struct OdcsRenderer {
+    schema: Vec<SchemaObject>,
+    /// `(project, dataset)` of every table, for the servers section.
+    datasets: BTreeSet<(String, String)>,
+    /// Schema object name per `project.dataset.table`.
+    schema_names: HashMap<String, String>,
+}
+
+/// The ODCS logical type for a BigQuery type.
+pub fn logical_type(field_type: &str) -> &'static str {
+    match field_type {
+        "INTEGER" | "INT64" => "integer",
+        "FLOAT" | "FLOAT64" | "NUMERIC" | "DECIMAL" | "BIGNUMERIC" | "BIGDECIMAL" => "number",
+        "BOOLEAN" | "BOOL" => "boolean",
+        "DATE" | "DATETIME" | "TIMESTAMP" => "date",
+        "RECORD" | "STRUCT" => "object",
+        _ => "string",
+    }
+}
+
+/// A single value of the column, ignoring REPEATED.
+fn value_property(column: &Column, name: String) -> SchemaProperty {
+    SchemaProperty {
+        name,
+        logical_type: Some(logical_type(&column.field_type).to_string()),
+        physical_type: Some(standard_sql_type(&column.field_type).to_string()),
+        required: false,
+        description: None,
+        primary_key: false,
+        primary_key_position: None,
+        partitioned: false,
+        partition_key_position: None,
+        properties: column.children().iter().map(column_property).collect(),
+        items: None,
+    }
+}
+
+fn column_property(column: &Column) -> SchemaProperty {
+    let mut property = if column.is_repeated() {
+        let items = value_property(column, String::new());
+        SchemaProperty {
+            logical_type: Some("array".to_string()),
+            physical_type: Some(format!(
+                "ARRAY<{}>",
+                items.physical_type.as_deref().unwrap_or_default()
+            )),
+            properties: Vec::new(),
+            items: Some(Box::new(items)),
+            ..value_property(column, column.name.clone())
+        }
+    } else {
+        value_property(column, column.name.clone())
+    };
+    property.required = column.is_required();
+    property.description = column.description.clone();
+    property
+}
+
+fn schema_object(table: &Table, name: String) -> SchemaObject {
+    let mut properties: Vec<SchemaProperty> = table
+        .schema
+        .as_ref()
+        .map(|schema| schema.fields.iter().map(column_property).collect())
+        .unwrap_or_default();
+
+    if let Some(primary_key) = table
+        .table_constraints
+        .as_ref()
+        .and_then(|constraints| constraints.primary_key.as_ref())
+    {
+        for (position, name) in primary_key.columns.iter().enumerate() {
+            if let Some(property) = properties.iter_mut().find(|p| &p.name == name) {
+                property.primary_key = true;
+                property.primary_key_position = Some(position + 1);
+            }
+        }
+    }
+
+    let partition_field = table
+        .time_partitioning
+        .as_ref()
+        .and_then(|partitioning| partitioning.field.as_ref())
+        .or(table
+            .range_partitioning
+            .as_ref()
+            .map(|partitioning| &partitioning.field));
+    if let Some(field) = partition_field {
+        if let Some(property) = properties.iter_mut().find(|p| &p.name == field) {
+            property.partitioned = true;
+            property.partition_key_position = Some(1);
+        }
+    }
+
+    let physical_type = match table.table_type.as_deref() {
+        Some("VIEW") => "view",
+        Some("MATERIALIZED_VIEW") => "materialized_view",
+        Some("EXTERNAL") => "external_table",
+        _ => "table",
+    };
+    SchemaObject {
+        name,
+        physical_name: Some(table.table_reference.to_str()),
+        physical_type: Some(physical_type.to_string()),
+        logical_type: Some("object".to_string()),
+        description: table.description.clone(),
+        properties,
+    }
+}
+
+impl SchemaRenderer for OdcsRenderer {
+    fn begin(&mut self, _out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.schema.clear();
+        self.datasets.clear();
+
+        // A qualified name can still match another table's ID, like `sales_orders` next to `sales.orders`.
+        let mut taken = HashSet::new();
+        self.schema_names = qualified_table_ids(tables)
+            .into_iter()
+            .map(|(reference, id)| {
+                let name = unique_name(&mut taken, &id, "_");
+                (reference, name)
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        self.datasets
+            .insert((reference.project_id.clone(), reference.dataset_id.clone()));
+        let name = self
+            .schema_names
+            .get(&reference.to_str())
+            .cloned()
+            .unwrap_or_else(|| reference.table_id.clone());
+        self.schema.push(schema_object(table, name));
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        let projects: BTreeSet<&str> = self.datasets.iter().map(|(p, _)| p.as_str()).collect();
+        let id = match (self.datasets.len(), projects.len()) {
+            (1, _) => {
+                let (project, dataset) = self.datasets.first().expect("one dataset");
+                format!("{}.{}", project, dataset)
+            }
+            (_, 1) => projects.first().expect("one project").to_string(),
+            _ => "bigquery".to_string(),
+        };
+
+        let servers = self
+            .datasets
+            .iter()
+            .map(|(project, dataset)| Server {
+                server: format!("{}.{}", project, dataset),
+                server_type: "bigquery".to_string(),
+                project: Some(project.clone()),
+                dataset: Some(dataset.clone()),
+            })
+            .collect();
+
+        let contract = DataContract {
+            api_version: API_VERSION.to_string(),
+            kind: "DataContract".to_string(),
+            id,
+            version: "1.0.0".to_string(),
+            status: "draft".to_string(),
+            servers,
+            schema: std::mem::take(&mut self.schema),
+        };
+        let yaml = serde_yaml::to_string(&contract).map_err(Error::other)?;
+        write!(out, "{}", yaml)
+    }
+
+    fn extension(&self) -> &'static str {
+        "odcs.yaml"
+    }
+
+    fn default_file_name(&self) -> String {
+        "datacontract.odcs.yaml".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::verify::verify;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(reference: &str) -> Table {
+        let reference: crate::bigquery::types::TableReference = reference.parse().unwrap();
+        serde_json::from_value(json!({
+            "tableReference": reference,
+            "description": "Orders",
+            "schema": { "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key" },
+                { "name": "day", "type": "DATE" },
+                { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                    { "name": "sku", "type": "STRING", "mode": "REQUIRED", "description": "Stock unit" },
+                    { "name": "price", "type": "NUMERIC" }
+                ] },
+                { "name": "address", "type": "RECORD", "fields": [
+                    { "name": "city", "type": "STRING" }
+                ] }
+            ] },
+            "timePartitioning": { "type": "DAY", "field": "day" },
+            "tableConstraints": { "primaryKey": { "columns": ["id"] } }
+        }))
+        .unwrap()
+    }
+
+    fn render(tables: &[Table]) -> DataContract {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut OdcsRenderer::default(), tables).unwrap();
+        serde_yaml::from_slice(&out).unwrap()
+    }
+
+    fn names(contract: &DataContract) -> Vec<&str> {
+        contract
+            .schema
+            .iter()
+            .map(|object| object.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn written_contracts_verify_against_their_tables() {
+        let tables = [
+            table("p1.sales.orders"),
+            table("p2.sales.orders"),
+            table("p1.ops.orders"),
+        ];
+        let contract = render(&tables);
+
+        let violations: Vec<String> = verify(&contract, &tables)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn columns_become_typed_properties() {
+        let contract = render(&[table("p.sales.orders")]);
+        assert_eq!(contract.id, "p.sales");
+        assert_eq!(contract.servers.len(), 1);
+
+        let object = &contract.schema[0];
+        assert_eq!(object.name, "orders");
+        assert_eq!(object.physical_name.as_deref(), Some("p.sales.orders"));
+        assert_eq!(object.physical_type.as_deref(), Some("table"));
+
+        let property = |name: &str| {
+            let property = object.properties.iter().find(|p| p.name == name).unwrap();
+            serde_json::to_value(property).unwrap()
+        };
+        assert_eq!(
+            property("id"),
+            json!({
+                "name": "id",
+                "logicalType": "integer",
+                "physicalType": "INT64",
+                "required": true,
+                "description": "Key",
+                "primaryKey": true,
+                "primaryKeyPosition": 1
+            })
+        );
+        assert_eq!(property("day")["partitioned"], true);
+        assert_eq!(
+            property("items"),
+            json!({
+                "name": "items",
+                "logicalType": "array",
+                "physicalType": "ARRAY<STRUCT>",
+                "items": {
+                    "logicalType": "object",
+                    "physicalType": "STRUCT",
+                    "properties": [
+                        {
+                            "name": "sku",
+                            "logicalType": "string",
+                            "physicalType": "STRING",
+                            "required": true,
+                            "description": "Stock unit"
+                        },
+                        { "name": "price", "logicalType": "number", "physicalType": "NUMERIC" }
+                    ]
+                }
+            })
+        );
+        assert_eq!(property("address")["properties"][0]["name"], "city");
+    }
+
+    #[test]
+    fn schema_names_are_qualified_as_far_as_needed() {
+        let contract = render(&[
+            table("p1.sales.orders"),
+            table("p2.sales.orders"),
+            table("p1.ops.orders"),
+            table("p1.ops.refunds"),
+            table("p1.ops.ops_orders"),
+        ]);
+
+        assert_eq!(
+            names(&contract),
+            [
+                "p1_sales_orders",
+                "p2_sales_orders",
+                "ops_orders",
+                "refunds",
+                "ops_orders_2"
+            ]
+        );
+        assert_eq!(contract.id, "bigquery");
+    }
+}
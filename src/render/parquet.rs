@@ -0,0 +1,168 @@
+//! Parquet message type schemas, as printed by `parquet-schema` and parsed by `parse_message_type`.
+//!
+//! Types match what the Storage Read API's Arrow types convert to: microsecond TIMESTAMP and TIME, DATE as int32,
+//! NUMERIC and BIGNUMERIC as fixed length DECIMALs. REPEATED columns use the standard three-level LIST structure.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::SchemaRenderer;
+use std::io::{Result, Write};
+
+pub struct ParquetRenderer;
+
+/// The physical type and the logical type annotation for a leaf column.
+fn primitive_type(field_type: &str) -> (&'static str, Option<&'static str>) {
+    match field_type {
+        "BOOLEAN" | "BOOL" => ("boolean", None),
+        "INTEGER" | "INT64" => ("int64", None),
+        "FLOAT" | "FLOAT64" => ("double", None),
+        "NUMERIC" | "DECIMAL" => ("fixed_len_byte_array(16)", Some("DECIMAL(38,9)")),
+        "BIGNUMERIC" | "BIGDECIMAL" => ("fixed_len_byte_array(32)", Some("DECIMAL(76,38)")),
+        "BYTES" => ("binary", None),
+        "DATE" => ("int32", Some("DATE")),
+        "TIME" => ("int64", Some("TIME(MICROS,false)")),
+        "DATETIME" => ("int64", Some("TIMESTAMP(MICROS,false)")),
+        "TIMESTAMP" => ("int64", Some("TIMESTAMP(MICROS,true)")),
+        "JSON" => ("binary", Some("JSON")),
+        _ => ("binary", Some("STRING")),
+    }
+}
+
+/// Writes a single value of the column under `name`, ignoring REPEATED.
+fn write_value(
+    out: &mut dyn Write,
+    column: &Column,
+    repetition: &str,
+    name: &str,
+    depth: usize,
+) -> Result<()> {
+    let indent = "  ".repeat(depth);
+    if column.is_record() {
+        writeln!(out, "{}{} group {} {{", indent, repetition, name)?;
+        for child in column.children() {
+            write_field(out, child, depth + 1)?;
+        }
+        return writeln!(out, "{}}}", indent);
+    }
+
+    let (physical, logical) = primitive_type(&column.field_type);
+    match logical {
+        Some(logical) => writeln!(
+            out,
+            "{}{} {} {} ({});",
+            indent, repetition, physical, name, logical
+        ),
+        None => writeln!(out, "{}{} {} {};", indent, repetition, physical, name),
+    }
+}
+
+fn write_field(out: &mut dyn Write, column: &Column, depth: usize) -> Result<()> {
+    if !column.is_repeated() {
+        let repetition = if column.is_required() {
+            "required"
+        } else {
+            "optional"
+        };
+        return write_value(out, column, repetition, &column.name, depth);
+    }
+
+    // BigQuery arrays and their elements are never NULL.
+    let indent = "  ".repeat(depth);
+    writeln!(out, "{}required group {} (LIST) {{", indent, column.name)?;
+    writeln!(out, "{}  repeated group list {{", indent)?;
+    write_value(out, column, "required", "element", depth + 2)?;
+    writeln!(out, "{}  }}", indent)?;
+    writeln!(out, "{}}}", indent)
+}
+
+impl SchemaRenderer for ParquetRenderer {
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        writeln!(out, "message {} {{", table.table_reference.table_id)?;
+        if let Some(schema) = &table.schema {
+            for column in &schema.fields {
+                write_field(out, column, 1)?;
+            }
+        }
+        writeln!(out, "}}\n")
+    }
+
+    fn extension(&self) -> &'static str {
+        "parquet.txt"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn render(fields: serde_json::Value) -> String {
+        let mut table = Table::from_reference("p.d.orders".parse().unwrap());
+        table.schema = Some(serde_json::from_value(json!({ "fields": fields })).unwrap());
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut ParquetRenderer, &[table]).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn columns_become_annotated_primitives() {
+        let output = render(json!([
+            { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+            { "name": "name", "type": "STRING" },
+            { "name": "total", "type": "NUMERIC" },
+            { "name": "day", "type": "DATE" },
+            { "name": "created_at", "type": "TIMESTAMP" },
+            { "name": "local_at", "type": "DATETIME" },
+            { "name": "payload", "type": "JSON" },
+            { "name": "raw", "type": "BYTES" }
+        ]));
+
+        assert_eq!(
+            output,
+            "message orders {
+  required int64 id;
+  optional binary name (STRING);
+  optional fixed_len_byte_array(16) total (DECIMAL(38,9));
+  optional int32 day (DATE);
+  optional int64 created_at (TIMESTAMP(MICROS,true));
+  optional int64 local_at (TIMESTAMP(MICROS,false));
+  optional binary payload (JSON);
+  optional binary raw;
+}
+
+"
+        );
+    }
+
+    #[test]
+    fn repeated_columns_use_the_three_level_list() {
+        let output = render(json!([
+            { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+            { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                { "name": "sku", "type": "STRING", "mode": "REQUIRED" },
+                { "name": "qty", "type": "INTEGER" }
+            ] }
+        ]));
+
+        assert_eq!(
+            output,
+            "message orders {
+  required group tags (LIST) {
+    repeated group list {
+      required binary element (STRING);
+    }
+  }
+  required group items (LIST) {
+    repeated group list {
+      required group element {
+        required binary sku (STRING);
+        optional int64 qty;
+      }
+    }
+  }
+}
+
+"
+        );
+    }
+}
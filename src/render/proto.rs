@@ -0,0 +1,221 @@
+//! Protocol Buffers (proto2) message definitions for the BigQuery Storage Write API.
+//!
+//! Types follow the Storage Write API's documented mapping: TIMESTAMP is int64 microseconds since the epoch, DATE is
+//! int32 days since the epoch, and NUMERIC, BIGNUMERIC, DATETIME, TIME, GEOGRAPHY and JSON are strings. Field numbers
+//! are assigned from 1 in column order, so they stay stable as long as columns are only appended.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::{pascal_case, table_type_names, unique_name, SchemaRenderer};
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+
+#[derive(Default)]
+pub struct ProtoRenderer {
+    /// Message name per table, see `table_type_names`.
+    message_names: HashMap<String, String>,
+}
+
+fn scalar_type(field_type: &str) -> &'static str {
+    match field_type {
+        "INTEGER" | "INT64" | "TIMESTAMP" => "int64",
+        "FLOAT" | "FLOAT64" => "double",
+        "BOOLEAN" | "BOOL" => "bool",
+        "BYTES" => "bytes",
+        "DATE" => "int32",
+        _ => "string",
+    }
+}
+
+fn write_comment(out: &mut dyn Write, text: &str, indent: &str) -> Result<()> {
+    for line in text.lines() {
+        writeln!(out, "{}// {}", indent, line)?;
+    }
+    Ok(())
+}
+
+/// Writes the message with a nested message per RECORD column. Nested messages are named after their column, and
+/// numbered where that clashes with a field or another nested message in the same scope, like `line_items` and
+/// `lineItems`.
+fn write_message(out: &mut dyn Write, name: &str, columns: &[Column], depth: usize) -> Result<()> {
+    let indent = "  ".repeat(depth);
+    let field_indent = "  ".repeat(depth + 1);
+    writeln!(out, "{}message {} {{", indent, name)?;
+
+    let mut taken: HashSet<String> = columns.iter().map(|column| column.name.clone()).collect();
+    let nested_names: Vec<Option<String>> = columns
+        .iter()
+        .map(|column| {
+            column
+                .is_record()
+                .then(|| unique_name(&mut taken, &pascal_case(&column.name), ""))
+        })
+        .collect();
+
+    for (column, nested_name) in columns.iter().zip(&nested_names) {
+        if let Some(nested_name) = nested_name {
+            write_message(out, nested_name, column.children(), depth + 1)?;
+            writeln!(out)?;
+        }
+    }
+
+    for (idx, (column, nested_name)) in columns.iter().zip(&nested_names).enumerate() {
+        let label = if column.is_repeated() {
+            "repeated"
+        } else if column.is_required() {
+            "required"
+        } else {
+            "optional"
+        };
+        let field_type = match nested_name {
+            Some(nested_name) => nested_name.clone(),
+            None => scalar_type(&column.field_type).to_string(),
+        };
+
+        if let Some(description) = &column.description {
+            write_comment(out, description, &field_indent)?;
+        }
+        writeln!(
+            out,
+            "{}{} {} {} = {};",
+            field_indent,
+            label,
+            field_type,
+            column.name,
+            idx + 1
+        )?;
+    }
+
+    writeln!(out, "{}}}", indent)
+}
+
+impl SchemaRenderer for ProtoRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.message_names = table_type_names(tables, &[]);
+        writeln!(out, "syntax = \"proto2\";")
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        let name = self
+            .message_names
+            .get(&reference.to_str())
+            .cloned()
+            .unwrap_or_else(|| pascal_case(&reference.table_id));
+
+        writeln!(out)?;
+        writeln!(out, "// {}", reference.to_str())?;
+        if let Some(description) = &table.description {
+            write_comment(out, description, "")?;
+        }
+
+        let columns = table
+            .schema
+            .as_ref()
+            .map(|schema| schema.fields.as_slice())
+            .unwrap_or(&[]);
+        write_message(out, &name, columns, 0)
+    }
+
+    fn extension(&self) -> &'static str {
+        "proto"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(reference: &str, fields: serde_json::Value) -> Table {
+        let mut table = Table::from_reference(reference.parse().unwrap());
+        table.schema = Some(serde_json::from_value(json!({ "fields": fields })).unwrap());
+        table
+    }
+
+    fn render(tables: &[Table]) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut ProtoRenderer::default(), tables).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn fields_get_labels_and_numbers_in_column_order() {
+        let mut orders = table(
+            "p.d.orders",
+            json!([
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key" },
+                { "name": "created_at", "type": "TIMESTAMP" },
+                { "name": "day", "type": "DATE" },
+                { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                    { "name": "sku", "type": "STRING", "mode": "REQUIRED" },
+                    { "name": "price", "type": "NUMERIC" }
+                ] },
+                { "name": "paid", "type": "BOOLEAN" }
+            ]),
+        );
+        orders.description = Some("One row per order".to_string());
+
+        assert_eq!(
+            render(&[orders]),
+            "syntax = \"proto2\";
+
+// p.d.orders
+// One row per order
+message Orders {
+  message Items {
+    required string sku = 1;
+    optional string price = 2;
+  }
+
+  // Key
+  required int64 id = 1;
+  optional int64 created_at = 2;
+  optional int32 day = 3;
+  repeated string tags = 4;
+  repeated Items items = 5;
+  optional bool paid = 6;
+}
+"
+        );
+    }
+
+    #[test]
+    fn nested_message_names_are_unique_in_their_scope() {
+        let city = json!({ "name": "city", "type": "STRING" });
+        let output = render(&[table(
+            "p.d.orders",
+            json!([
+                { "name": "line_items", "type": "RECORD", "fields": [city] },
+                { "name": "lineItems", "type": "RECORD", "fields": [city] },
+                { "name": "AddressBook", "type": "STRING" },
+                { "name": "address_book", "type": "RECORD", "fields": [
+                    { "name": "line_items", "type": "RECORD", "fields": [city] }
+                ] }
+            ]),
+        )]);
+
+        assert!(output.contains("  message LineItems {\n"));
+        assert!(output.contains("  message LineItems2 {\n"));
+        assert!(output.contains("  message AddressBook2 {\n"));
+        // A new scope can reuse the name.
+        assert!(output.contains("    message LineItems {\n"));
+        assert!(output.contains("  optional LineItems line_items = 1;\n"));
+        assert!(output.contains("  optional LineItems2 lineItems = 2;\n"));
+        assert!(output.contains("  optional string AddressBook = 3;\n"));
+        assert!(output.contains("  optional AddressBook2 address_book = 4;\n"));
+    }
+
+    #[test]
+    fn messages_are_named_per_table() {
+        let fields = json!([{ "name": "id", "type": "INTEGER" }]);
+        let output = render(&[
+            table("p.sales.orders", fields.clone()),
+            table("p.ops.orders", fields),
+        ]);
+
+        assert!(output.contains("message SalesOrders {"));
+        assert!(output.contains("message OpsOrders {"));
+    }
+}
@@ -0,0 +1,424 @@
+//! Python row models, either as `dataclasses` or as `TypedDict`s. RECORD columns become their own classes,
+//! REPEATED columns `List`s and NULLABLE columns `Optional`s. Types follow what the BigQuery Python client returns:
+//! `decimal.Decimal` for NUMERIC and BIGNUMERIC, `datetime` types for the date and time types, `bytes` for BYTES.
+//!
+//! Dataclasses are `kw_only` (Python 3.10+) so optional fields can keep their column order. Column names that aren't
+//! valid Python identifiers get a trailing underscore in dataclasses, as does `dataclasses`, which the class body
+//! refers to; TypedDicts fall back to the functional syntax.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::{pascal_case, table_type_names, unique_name, SchemaRenderer};
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+
+pub struct PythonRenderer {
+    typed_dict: bool,
+    /// Class name per table, see `table_type_names`.
+    class_names: HashMap<String, String>,
+    /// Every class name in the module so far, so nested class names don't clash.
+    type_names: HashSet<String>,
+}
+
+impl PythonRenderer {
+    pub fn dataclasses() -> Self {
+        PythonRenderer {
+            typed_dict: false,
+            class_names: HashMap::new(),
+            type_names: HashSet::new(),
+        }
+    }
+
+    pub fn typed_dicts() -> Self {
+        PythonRenderer {
+            typed_dict: true,
+            class_names: HashMap::new(),
+            type_names: HashSet::new(),
+        }
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// The names the module imports from `typing`, which a class mustn't shadow.
+const RESERVED_TYPES: &[&str] = &["Any", "List", "Optional", "TypedDict"];
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c == '_' || c.is_ascii_alphanumeric())
+        && !KEYWORDS.contains(&name)
+}
+
+/// The dataclass attribute for a column. A `dataclasses` field's default would rebind the module for the rest of the
+/// class body, so it's renamed too.
+fn attribute_name(name: &str) -> String {
+    if is_identifier(name) && name != "dataclasses" {
+        return name.to_string();
+    }
+    let mut attribute: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if attribute.is_empty() || attribute.starts_with(|c: char| c.is_ascii_digit()) {
+        attribute.insert(0, '_');
+    }
+    attribute.push('_');
+    attribute
+}
+
+/// A double-quoted string literal. Non-ASCII characters are kept as they are, since Python source is UTF-8.
+fn string_literal(text: &str) -> String {
+    let mut literal = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+fn scalar_type(field_type: &str) -> &'static str {
+    match field_type {
+        "INTEGER" | "INT64" => "int",
+        "FLOAT" | "FLOAT64" => "float",
+        "BOOLEAN" | "BOOL" => "bool",
+        "BYTES" => "bytes",
+        "NUMERIC" | "BIGNUMERIC" | "DECIMAL" | "BIGDECIMAL" => "decimal.Decimal",
+        "TIMESTAMP" | "DATETIME" => "datetime.datetime",
+        "DATE" => "datetime.date",
+        "TIME" => "datetime.time",
+        "JSON" => "Any",
+        _ => "str",
+    }
+}
+
+fn write_docstring(out: &mut dyn Write, text: &str, indent: &str) -> Result<()> {
+    let text = text.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() == 1 {
+        return writeln!(out, "{}\"\"\"{}\"\"\"", indent, lines[0]);
+    }
+
+    writeln!(out, "{}\"\"\"", indent)?;
+    for line in lines {
+        let line = line.trim_end();
+        if line.is_empty() {
+            writeln!(out)?;
+        } else {
+            writeln!(out, "{}{}", indent, line)?;
+        }
+    }
+    writeln!(out, "{}\"\"\"", indent)
+}
+
+impl PythonRenderer {
+    /// Writes the class, then the classes for its RECORD columns. Nested classes are named `{Parent}{Column}`, with a
+    /// number added when that's taken, e.g. by `foo_bar` and `fooBar` in the same RECORD or by another table's class.
+    fn write_class(
+        &mut self,
+        out: &mut dyn Write,
+        name: &str,
+        description: Option<&String>,
+        columns: &[Column],
+    ) -> Result<()> {
+        let mut nested = Vec::new();
+        let mut fields = Vec::new();
+        for column in columns {
+            let value_type = if column.is_record() {
+                let nested_name = unique_name(
+                    &mut self.type_names,
+                    &format!("{}{}", name, pascal_case(&column.name)),
+                    "",
+                );
+                nested.push((nested_name.clone(), column));
+                nested_name
+            } else {
+                scalar_type(&column.field_type).to_string()
+            };
+            let field_type = if column.is_repeated() {
+                format!("List[{}]", value_type)
+            } else if column.is_required() {
+                value_type
+            } else {
+                format!("Optional[{}]", value_type)
+            };
+            fields.push((column, field_type));
+        }
+
+        writeln!(out, "\n")?;
+        if self.typed_dict && columns.iter().any(|column| !is_identifier(&column.name)) {
+            if let Some(description) = description {
+                for line in description.lines() {
+                    writeln!(out, "# {}", line)?;
+                }
+            }
+            writeln!(out, "{} = TypedDict(", name)?;
+            writeln!(out, "    {},", string_literal(name))?;
+            writeln!(out, "    {{")?;
+            // The functional syntax evaluates types right away, so they're quoted as forward references.
+            for (column, field_type) in &fields {
+                writeln!(
+                    out,
+                    "        {}: {},",
+                    string_literal(&column.name),
+                    string_literal(field_type)
+                )?;
+            }
+            writeln!(out, "    }},")?;
+            writeln!(out, ")")?;
+        } else {
+            if self.typed_dict {
+                writeln!(out, "class {}(TypedDict):", name)?;
+            } else {
+                writeln!(out, "@dataclasses.dataclass(kw_only=True)")?;
+                writeln!(out, "class {}:", name)?;
+            }
+            if let Some(description) = description {
+                write_docstring(out, description, "    ")?;
+            }
+            if fields.is_empty() {
+                writeln!(out, "    pass")?;
+            }
+            for (column, field_type) in &fields {
+                let default = match self.typed_dict {
+                    true => "",
+                    false if column.is_repeated() => " = dataclasses.field(default_factory=list)",
+                    false if column.is_required() => "",
+                    false => " = None",
+                };
+                // TypedDict keys are the column names, which are all identifiers here.
+                let attribute = match self.typed_dict {
+                    true => column.name.clone(),
+                    false => attribute_name(&column.name),
+                };
+                writeln!(out, "    {}: {}{}", attribute, field_type, default)?;
+                if let Some(description) = &column.description {
+                    write_docstring(out, description, "    ")?;
+                }
+            }
+        }
+
+        for (nested_name, column) in nested {
+            self.write_class(
+                out,
+                &nested_name,
+                column.description.as_ref(),
+                column.children(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl SchemaRenderer for PythonRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.class_names = table_type_names(tables, RESERVED_TYPES);
+        self.type_names = self.class_names.values().cloned().collect();
+        self.type_names
+            .extend(RESERVED_TYPES.iter().map(|name| name.to_string()));
+        writeln!(out, "# Generated by schema-searcher.")?;
+        writeln!(out, "from __future__ import annotations")?;
+        writeln!(out)?;
+        writeln!(out, "import datetime")?;
+        writeln!(out, "import decimal")?;
+        if self.typed_dict {
+            writeln!(out, "from typing import Any, List, Optional, TypedDict")
+        } else {
+            writeln!(out, "import dataclasses")?;
+            writeln!(out, "from typing import Any, List, Optional")
+        }
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        let name = self
+            .class_names
+            .get(&reference.to_str())
+            .cloned()
+            .unwrap_or_else(|| pascal_case(&reference.table_id));
+        let description = match &table.description {
+            Some(description) => format!("{}\n\n{}", reference.to_str(), description),
+            None => reference.to_str(),
+        };
+        let columns = table
+            .schema
+            .as_ref()
+            .map(|schema| schema.fields.as_slice())
+            .unwrap_or(&[]);
+
+        self.write_class(out, &name, Some(&description), columns)
+    }
+
+    fn extension(&self) -> &'static str {
+        "py"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(reference: &str, fields: serde_json::Value) -> Table {
+        let mut table = Table::from_reference(reference.parse().unwrap());
+        table.schema = Some(serde_json::from_value(json!({ "fields": fields })).unwrap());
+        table
+    }
+
+    fn render(renderer: &mut PythonRenderer, tables: &[Table]) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, renderer, tables).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn dataclass_fields_get_types_and_defaults() {
+        let output = render(
+            &mut PythonRenderer::dataclasses(),
+            &[table(
+                "p.d.orders",
+                json!([
+                    { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key" },
+                    { "name": "total", "type": "NUMERIC" },
+                    { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+                    { "name": "class", "type": "DATE" }
+                ]),
+            )],
+        );
+
+        assert_eq!(
+            output,
+            "# Generated by schema-searcher.
+from __future__ import annotations
+
+import datetime
+import decimal
+import dataclasses
+from typing import Any, List, Optional
+
+
+@dataclasses.dataclass(kw_only=True)
+class Orders:
+    \"\"\"p.d.orders\"\"\"
+    id: int
+    \"\"\"Key\"\"\"
+    total: Optional[decimal.Decimal] = None
+    tags: List[str] = dataclasses.field(default_factory=list)
+    class_: Optional[datetime.date] = None
+"
+        );
+    }
+
+    #[test]
+    fn columns_named_after_the_module_api_do_not_shadow_it() {
+        let output = render(
+            &mut PythonRenderer::dataclasses(),
+            &[table(
+                "p.d.orders",
+                json!([
+                    { "name": "field", "type": "STRING" },
+                    { "name": "dataclasses", "type": "STRING" },
+                    { "name": "tags", "type": "STRING", "mode": "REPEATED" }
+                ]),
+            )],
+        );
+
+        assert!(!output.contains("from dataclasses import"));
+        assert!(output.contains("    field: Optional[str] = None\n"));
+        assert!(output.contains("    dataclasses_: Optional[str] = None\n"));
+        assert!(output.contains("    tags: List[str] = dataclasses.field(default_factory=list)\n"));
+    }
+
+    #[test]
+    fn typed_dicts_fall_back_to_the_functional_syntax() {
+        let output = render(
+            &mut PythonRenderer::typed_dicts(),
+            &[
+                table(
+                    "p.d.orders",
+                    json!([
+                        { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+                        { "name": "dataclasses", "type": "STRING" }
+                    ]),
+                ),
+                table(
+                    "p.d.events",
+                    json!([{ "name": "event-id", "type": "STRING" }]),
+                ),
+            ],
+        );
+
+        assert!(output.contains("from typing import Any, List, Optional, TypedDict\n"));
+        assert!(output.contains("    dataclasses: Optional[str]\n"));
+        assert!(
+            output.contains("class Orders(TypedDict):\n    \"\"\"p.d.orders\"\"\"\n    id: int\n")
+        );
+        assert!(output.contains(
+            "Events = TypedDict(\n    \"Events\",\n    {\n        \"event-id\": \"Optional[str]\",\n    },\n)\n"
+        ));
+    }
+
+    #[test]
+    fn sibling_nested_names_are_unique() {
+        let sku = json!({ "name": "sku", "type": "STRING" });
+        let output = render(
+            &mut PythonRenderer::dataclasses(),
+            &[table(
+                "p.d.orders",
+                json!([
+                    { "name": "order_items", "type": "RECORD", "fields": [sku] },
+                    { "name": "orderItems", "type": "RECORD", "fields": [sku] }
+                ]),
+            )],
+        );
+
+        assert!(output.contains("    order_items: Optional[OrdersOrderItems] = None\n"));
+        assert!(output.contains("    orderItems: Optional[OrdersOrderItems2] = None\n"));
+        assert!(output.contains("class OrdersOrderItems:\n"));
+        assert!(output.contains("class OrdersOrderItems2:\n"));
+    }
+
+    #[test]
+    fn class_names_do_not_clash_with_tables_or_typing() {
+        let sku = json!({ "name": "sku", "type": "STRING" });
+        let output = render(
+            &mut PythonRenderer::dataclasses(),
+            &[
+                table(
+                    "p.d.orders",
+                    json!([{ "name": "items", "type": "RECORD", "fields": [sku] }]),
+                ),
+                table("p.d.orders_items", json!([sku])),
+                table("p.d.list", json!([sku])),
+            ],
+        );
+
+        assert!(output.contains("    items: Optional[OrdersItems2] = None\n"));
+        assert!(output.contains("class OrdersItems2:\n"));
+        assert_eq!(output.matches("class OrdersItems:").count(), 1);
+        assert!(output.contains("class OrdersItems:\n    \"\"\"p.d.orders_items\"\"\"\n"));
+        assert!(output.contains("class List2:\n"));
+    }
+
+    #[test]
+    fn string_literals_are_escaped() {
+        assert_eq!(string_literal("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(string_literal("a\\b"), "\"a\\\\b\"");
+        assert_eq!(string_literal("a\nb\tc\r"), "\"a\\nb\\tc\\r\"");
+        assert_eq!(string_literal("\u{0}\u{7f}"), "\"\\u0000\\u007f\"");
+        assert_eq!(string_literal("café"), "\"café\"");
+    }
+}
@@ -0,0 +1,372 @@
+//! Starter `SELECT` queries: every leaf column of each table, with REPEATED fields flattened.
+//!
+//! Each REPEATED column gets a `LEFT JOIN UNNEST(...)` aliased by its dotted path with underscores, so rows with
+//! empty arrays are kept. Leaf columns are aliased the same way. Partitioned tables get a filter on
+//! `@start_date`/`@end_date` (or `@range_start`/`@range_end`) query parameters to fill in.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::SchemaRenderer;
+use std::collections::HashSet;
+use std::io::{Result, Write};
+
+pub struct QueryRenderer;
+
+/// The alias of the table itself in the generated queries.
+const TABLE_ALIAS: &str = "t";
+
+/// GoogleSQL reserved keywords, which have to be quoted to be used as identifiers.
+const RESERVED_KEYWORDS: &[&str] = &[
+    "ALL",
+    "AND",
+    "ANY",
+    "ARRAY",
+    "AS",
+    "ASC",
+    "ASSERT_ROWS_MODIFIED",
+    "AT",
+    "BETWEEN",
+    "BY",
+    "CASE",
+    "CAST",
+    "COLLATE",
+    "CONTAINS",
+    "CREATE",
+    "CROSS",
+    "CUBE",
+    "CURRENT",
+    "DEFAULT",
+    "DEFINE",
+    "DESC",
+    "DISTINCT",
+    "ELSE",
+    "END",
+    "ENUM",
+    "ESCAPE",
+    "EXCEPT",
+    "EXCLUDE",
+    "EXISTS",
+    "EXTRACT",
+    "FALSE",
+    "FETCH",
+    "FOLLOWING",
+    "FOR",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "GROUPING",
+    "GROUPS",
+    "HASH",
+    "HAVING",
+    "IF",
+    "IGNORE",
+    "IN",
+    "INNER",
+    "INTERSECT",
+    "INTERVAL",
+    "INTO",
+    "IS",
+    "JOIN",
+    "LATERAL",
+    "LEFT",
+    "LIKE",
+    "LIMIT",
+    "LOOKUP",
+    "MERGE",
+    "NATURAL",
+    "NEW",
+    "NO",
+    "NOT",
+    "NULL",
+    "NULLS",
+    "OF",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OVER",
+    "PARTITION",
+    "PRECEDING",
+    "PROTO",
+    "QUALIFY",
+    "RANGE",
+    "RECURSIVE",
+    "RESPECT",
+    "RIGHT",
+    "ROLLUP",
+    "ROWS",
+    "SELECT",
+    "SET",
+    "SOME",
+    "STRUCT",
+    "TABLESAMPLE",
+    "THEN",
+    "TO",
+    "TREAT",
+    "TRUE",
+    "UNBOUNDED",
+    "UNION",
+    "UNNEST",
+    "USING",
+    "WHEN",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+    "WITHIN",
+];
+
+/// The name as is when it's a plain identifier, otherwise in backticks.
+fn identifier(name: &str) -> String {
+    let plain = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !RESERVED_KEYWORDS.contains(&name.to_ascii_uppercase().as_str());
+    if plain {
+        name.to_string()
+    } else {
+        format!("`{}`", name.replace('`', "\\`"))
+    }
+}
+
+/// Returns `name`, or `name_2`, `name_3`, ... when it's taken, and marks the result as taken.
+fn unique(name: String, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    let mut suffix = 2;
+    while !taken.insert(candidate.to_lowercase()) {
+        candidate = format!("{}_{}", name, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+#[derive(Default)]
+struct Query {
+    /// Select list entries, already including any `AS` alias.
+    select: Vec<String>,
+    joins: Vec<String>,
+    join_aliases: HashSet<String>,
+    output_names: HashSet<String>,
+}
+
+impl Query {
+    /// Adds `columns`, which are reached through `source`, the table alias or an UNNEST alias or a path from one.
+    fn add_columns(&mut self, columns: &[Column], source: &str, path: &[&str]) {
+        for column in columns {
+            let path = [path, &[column.name.as_str()]].concat();
+            let mut expression = format!("{}.{}", source, identifier(&column.name));
+
+            if column.is_repeated() {
+                let alias = identifier(&unique(path.join("_"), &mut self.join_aliases));
+                self.joins
+                    .push(format!("LEFT JOIN UNNEST({}) AS {}", expression, alias));
+                expression = alias;
+            }
+
+            if column.is_record() {
+                self.add_columns(column.children(), &expression, &path);
+                continue;
+            }
+
+            let output = identifier(&unique(path.join("_"), &mut self.output_names));
+            if expression == output || expression.ends_with(&format!(".{}", output)) {
+                self.select.push(expression);
+            } else {
+                self.select.push(format!("{} AS {}", expression, output));
+            }
+        }
+    }
+}
+
+/// A filter on the partitioning column with query parameters as placeholders, and a comment saying what to fill in.
+fn partition_filter(table: &Table) -> Option<(String, String)> {
+    if let Some(partitioning) = &table.range_partitioning {
+        let field = format!("{}.{}", TABLE_ALIAS, identifier(&partitioning.field));
+        return Some((
+            "-- Partition filter: set @range_start and @range_end (INT64).".to_string(),
+            format!("{} >= @range_start AND {} < @range_end", field, field),
+        ));
+    }
+
+    let partitioning = table.time_partitioning.as_ref()?;
+    let (field, field_type) = match &partitioning.field {
+        Some(field) => {
+            let field_type = table
+                .schema
+                .as_ref()
+                .and_then(|schema| schema.fields.iter().find(|c| &c.name == field))
+                .map(|c| c.field_type.as_str())
+                .unwrap_or("TIMESTAMP");
+            (format!("{}.{}", TABLE_ALIAS, identifier(field)), field_type)
+        }
+        None => ("_PARTITIONTIME".to_string(), "TIMESTAMP"),
+    };
+
+    let comment = "-- Partition filter: set @start_date and @end_date (DATE).".to_string();
+    let filter = match field_type {
+        "DATE" => format!("{} BETWEEN @start_date AND @end_date", field),
+        "DATETIME" => format!(
+            "{} >= DATETIME(@start_date) AND {} < DATETIME(DATE_ADD(@end_date, INTERVAL 1 DAY))",
+            field, field
+        ),
+        _ => format!(
+            "{} >= TIMESTAMP(@start_date) AND {} < TIMESTAMP(DATE_ADD(@end_date, INTERVAL 1 DAY))",
+            field, field
+        ),
+    };
+    Some((comment, filter))
+}
+
+impl SchemaRenderer for QueryRenderer {
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = table.table_reference.to_str();
+        writeln!(out, "\n-- {}", reference)?;
+
+        let columns = match &table.schema {
+            Some(schema) if !schema.fields.is_empty() => &schema.fields,
+            _ => return writeln!(out, "-- No schema available for {}", reference),
+        };
+
+        let mut query = Query::default();
+        query.join_aliases.insert(TABLE_ALIAS.to_string());
+        query.add_columns(columns, TABLE_ALIAS, &[]);
+
+        writeln!(out, "SELECT")?;
+        writeln!(out, "  {}", query.select.join(",\n  "))?;
+        write!(
+            out,
+            "FROM `{}` AS {}",
+            reference.replace('`', "\\`"),
+            TABLE_ALIAS
+        )?;
+        for join in &query.joins {
+            write!(out, "\n{}", join)?;
+        }
+        if let Some((comment, filter)) = partition_filter(table) {
+            write!(out, "\nWHERE\n  {}\n  {}", comment, filter)?;
+        }
+        writeln!(out, ";")
+    }
+
+    fn extension(&self) -> &'static str {
+        "sql"
+    }
+
+    fn default_file_name(&self) -> String {
+        "queries.sql".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(extra: serde_json::Value, fields: serde_json::Value) -> Table {
+        let mut table = json!({
+            "tableReference": { "projectId": "p", "datasetId": "d", "tableId": "orders" },
+            "schema": { "fields": fields }
+        });
+        table
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(table).unwrap()
+    }
+
+    fn render(table: Table) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut QueryRenderer, &[table]).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn repeated_fields_are_unnested_and_aliased_by_path() {
+        let output = render(table(
+            json!({}),
+            json!([
+                { "name": "id", "type": "INTEGER" },
+                { "name": "order", "type": "STRING" },
+                { "name": "items_sku", "type": "STRING" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                    { "name": "sku", "type": "STRING" },
+                    { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+                    { "name": "price", "type": "RECORD", "fields": [
+                        { "name": "amount", "type": "NUMERIC" }
+                    ] }
+                ] }
+            ]),
+        ));
+
+        assert_eq!(
+            output,
+            "
+-- p.d.orders
+SELECT
+  t.id,
+  t.`order`,
+  t.items_sku,
+  items.sku AS items_sku_2,
+  items_tags,
+  items.price.amount AS items_price_amount
+FROM `p.d.orders` AS t
+LEFT JOIN UNNEST(t.items) AS items
+LEFT JOIN UNNEST(items.tags) AS items_tags;
+"
+        );
+    }
+
+    #[test]
+    fn join_aliases_do_not_shadow_the_table_alias() {
+        let output = render(table(
+            json!({}),
+            json!([{ "name": "t", "type": "STRING", "mode": "REPEATED" }]),
+        ));
+
+        assert!(output.contains("  t_2 AS t\n"));
+        assert!(output.contains("LEFT JOIN UNNEST(t.t) AS t_2;\n"));
+    }
+
+    #[test]
+    fn partitioned_tables_get_a_filter() {
+        let fields = json!([
+            { "name": "day", "type": "DATE" },
+            { "name": "created_at", "type": "TIMESTAMP" },
+            { "name": "bucket", "type": "INTEGER" }
+        ]);
+
+        let by_date = render(table(
+            json!({ "timePartitioning": { "type": "DAY", "field": "day" } }),
+            fields.clone(),
+        ));
+        assert!(by_date.ends_with(
+            "FROM `p.d.orders` AS t
+WHERE
+  -- Partition filter: set @start_date and @end_date (DATE).
+  t.day BETWEEN @start_date AND @end_date;
+"
+        ));
+
+        let by_ingestion = render(table(
+            json!({ "timePartitioning": { "type": "DAY" } }),
+            fields.clone(),
+        ));
+        assert!(by_ingestion.ends_with(
+            "  _PARTITIONTIME >= TIMESTAMP(@start_date) AND _PARTITIONTIME < TIMESTAMP(DATE_ADD(@end_date, INTERVAL 1 DAY));\n"
+        ));
+
+        let by_range = render(table(
+            json!({ "rangePartitioning": {
+                "field": "bucket",
+                "range": { "start": "0", "end": "100", "interval": "10" }
+            } }),
+            fields,
+        ));
+        assert!(by_range.ends_with(
+            "  -- Partition filter: set @range_start and @range_end (INT64).
+  t.bucket >= @range_start AND t.bucket < @range_end;
+"
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+//! Rust struct generation. Each table becomes a `serde`-derivable struct, with a nested struct per RECORD column.
+//!
+//! NULLABLE columns are `Option`, REPEATED columns are `Vec`. The generated code needs `serde` (with `derive`),
+//! `serde_json` and `chrono` (with `serde`):
+//!
+//! | BigQuery | Rust |
+//! | --- | --- |
+//! | INT64 | `i64` |
+//! | FLOAT64 | `f64` |
+//! | BOOL | `bool` |
+//! | NUMERIC, BIGNUMERIC | `String`, to keep every digit |
+//! | TIMESTAMP | `chrono::DateTime<chrono::Utc>` |
+//! | DATETIME, DATE, TIME | `chrono::NaiveDateTime`, `NaiveDate`, `NaiveTime` |
+//! | JSON | `serde_json::Value` |
+//! | STRING, BYTES (base64), GEOGRAPHY (WKT), anything else | `String` |
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::{pascal_case, table_type_names, unique_name, SchemaRenderer};
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+
+#[derive(Default)]
+pub struct RustRenderer {
+    /// Struct name per table, see `table_type_names`.
+    struct_names: HashMap<String, String>,
+    /// Every struct name in the document so far, so nested struct names don't clash.
+    type_names: HashSet<String>,
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Type names a table's struct mustn't take: the prelude types and derives the generated code uses unqualified and
+/// `Self`. The chrono and serde_json types are always written with their crate path, but are kept free too so a
+/// struct called `DateTime` doesn't read like chrono's.
+const RESERVED_TYPES: &[&str] = &[
+    "Self",
+    "Option",
+    "Some",
+    "None",
+    "Result",
+    "Ok",
+    "Err",
+    "String",
+    "Vec",
+    "Box",
+    "Debug",
+    "Clone",
+    "PartialEq",
+    "Serialize",
+    "Deserialize",
+    "DateTime",
+    "Utc",
+    "NaiveDate",
+    "NaiveTime",
+    "NaiveDateTime",
+    "Value",
+];
+
+/// A snake_case field name for the column, and whether it differs from the column name and needs a serde rename.
+fn field_name(name: &str) -> (String, bool) {
+    let mut snake = String::new();
+    for (idx, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if idx > 0 && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            snake.push(c);
+        } else {
+            snake.push('_');
+        }
+    }
+    if snake.is_empty() || snake.starts_with(|c: char| c.is_ascii_digit()) {
+        snake.insert(0, '_');
+    }
+
+    let renamed = snake != name;
+    match snake.as_str() {
+        // These can't be raw identifiers.
+        "self" | "Self" | "super" | "crate" | "_" => (format!("{}_", snake), true),
+        keyword if KEYWORDS.contains(&keyword) => (format!("r#{}", snake), renamed),
+        _ => (snake, renamed),
+    }
+}
+
+fn scalar_type(field_type: &str) -> &'static str {
+    match field_type {
+        "INTEGER" | "INT64" => "i64",
+        "FLOAT" | "FLOAT64" => "f64",
+        "BOOLEAN" | "BOOL" => "bool",
+        "TIMESTAMP" => "chrono::DateTime<chrono::Utc>",
+        "DATETIME" => "chrono::NaiveDateTime",
+        "DATE" => "chrono::NaiveDate",
+        "TIME" => "chrono::NaiveTime",
+        "JSON" => "serde_json::Value",
+        _ => "String",
+    }
+}
+
+fn write_doc(out: &mut dyn Write, text: &str, indent: &str) -> Result<()> {
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            writeln!(out, "{}///", indent)?;
+        } else {
+            writeln!(out, "{}/// {}", indent, line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the struct, then the structs for its RECORD columns. Nested structs are named `{Parent}{Column}`, with a
+/// number added when that's taken, e.g. by `foo_bar` and `fooBar` in the same RECORD.
+fn write_struct(
+    out: &mut dyn Write,
+    type_names: &mut HashSet<String>,
+    name: &str,
+    description: Option<&String>,
+    columns: &[Column],
+) -> Result<()> {
+    writeln!(out)?;
+    if let Some(description) = description {
+        write_doc(out, description, "")?;
+    }
+    writeln!(
+        out,
+        "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]"
+    )?;
+    writeln!(out, "pub struct {} {{", name)?;
+
+    let mut nested = Vec::new();
+    let mut field_names = HashSet::new();
+    for column in columns {
+        let value_type = if column.is_record() {
+            let nested_name = unique_name(
+                type_names,
+                &format!("{}{}", name, pascal_case(&column.name)),
+                "",
+            );
+            nested.push((nested_name.clone(), column));
+            nested_name
+        } else {
+            scalar_type(&column.field_type).to_string()
+        };
+        let field_type = if column.is_repeated() {
+            format!("Vec<{}>", value_type)
+        } else if column.is_required() {
+            value_type
+        } else {
+            format!("Option<{}>", value_type)
+        };
+
+        if let Some(description) = &column.description {
+            write_doc(out, description, "    ")?;
+        }
+        // Deduplicated without the `r#`, since `type_2` needs none.
+        let (field, renamed) = match field_name(&column.name) {
+            (field, renamed) if field_names.insert(field.trim_start_matches("r#").to_string()) => {
+                (field, renamed)
+            }
+            (field, _) => (
+                unique_name(&mut field_names, field.trim_start_matches("r#"), "_"),
+                true,
+            ),
+        };
+        if renamed {
+            writeln!(out, "    #[serde(rename = {:?})]", column.name)?;
+        }
+        // BigQuery leaves out empty arrays in some APIs.
+        if column.is_repeated() {
+            writeln!(out, "    #[serde(default)]")?;
+        }
+        writeln!(out, "    pub {}: {},", field, field_type)?;
+    }
+    writeln!(out, "}}")?;
+
+    for (nested_name, column) in nested {
+        write_struct(
+            out,
+            type_names,
+            &nested_name,
+            column.description.as_ref(),
+            column.children(),
+        )?;
+    }
+    Ok(())
+}
+
+impl SchemaRenderer for RustRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.struct_names = table_type_names(tables, RESERVED_TYPES);
+        self.type_names = self.struct_names.values().cloned().collect();
+        self.type_names
+            .extend(RESERVED_TYPES.iter().map(|name| name.to_string()));
+        writeln!(out, "// Generated by schema-searcher.")?;
+        writeln!(
+            out,
+            "// Requires serde (derive), serde_json and chrono (serde) as dependencies."
+        )?;
+        writeln!(out)?;
+        writeln!(out, "use serde::{{Deserialize, Serialize}};")
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        let name = self
+            .struct_names
+            .get(&reference.to_str())
+            .cloned()
+            .unwrap_or_else(|| pascal_case(&reference.table_id));
+        let description = match &table.description {
+            Some(description) => format!("`{}`\n\n{}", reference.to_str(), description),
+            None => format!("`{}`", reference.to_str()),
+        };
+        let columns = table
+            .schema
+            .as_ref()
+            .map(|schema| schema.fields.as_slice())
+            .unwrap_or(&[]);
+
+        write_struct(
+            out,
+            &mut self.type_names,
+            &name,
+            Some(&description),
+            columns,
+        )
+    }
+
+    fn extension(&self) -> &'static str {
+        "rs"
+    }
+}
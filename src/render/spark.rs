@@ -0,0 +1,324 @@
+//! Spark `StructType` schemas for reading BigQuery tables with the spark-bigquery connector, either as Scala code or
+//! in the JSON format of `df.schema.json()` (which PySpark loads with `StructType.fromJson`).
+//!
+//! Types follow the connector: TIME is microseconds since midnight as `LongType`, DATETIME is `TimestampNTZType`
+//! (Spark 3.4+), and GEOGRAPHY, JSON and BIGNUMERIC, whose 76 digits don't fit Spark's decimals, are strings.
+//! REPEATED columns are non-null arrays of non-null elements, as BigQuery arrays can't hold NULLs. Column
+//! descriptions go into the field metadata under `description`.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::{table_type_names, SchemaRenderer};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::io::{Result, Write};
+
+pub struct SparkRenderer {
+    scala: bool,
+    /// JSON output: the schemas, written as one document in `end`.
+    schemas: Vec<(String, Value)>,
+    /// Scala output: the object member name of each table's schema.
+    type_names: HashMap<String, String>,
+}
+
+impl SparkRenderer {
+    pub fn scala() -> Self {
+        SparkRenderer {
+            scala: true,
+            schemas: Vec::new(),
+            type_names: HashMap::new(),
+        }
+    }
+
+    pub fn json() -> Self {
+        SparkRenderer {
+            scala: false,
+            ..Self::scala()
+        }
+    }
+}
+
+/// The JSON name and the Scala singleton of the Spark type for a non-RECORD BigQuery type.
+fn primitive_type(field_type: &str) -> (&'static str, &'static str) {
+    match field_type {
+        "BOOLEAN" | "BOOL" => ("boolean", "BooleanType"),
+        "INTEGER" | "INT64" | "TIME" => ("long", "LongType"),
+        "FLOAT" | "FLOAT64" => ("double", "DoubleType"),
+        "NUMERIC" | "DECIMAL" => ("decimal(38,9)", "DecimalType(38, 9)"),
+        "BYTES" => ("binary", "BinaryType"),
+        "DATE" => ("date", "DateType"),
+        "DATETIME" => ("timestamp_ntz", "TimestampNTZType"),
+        "TIMESTAMP" => ("timestamp", "TimestampType"),
+        _ => ("string", "StringType"),
+    }
+}
+
+fn nullable(column: &Column) -> bool {
+    !column.is_required() && !column.is_repeated()
+}
+
+fn json_struct(columns: &[Column]) -> Value {
+    let fields: Vec<Value> = columns.iter().map(json_field).collect();
+    json!({ "type": "struct", "fields": fields })
+}
+
+fn json_field(column: &Column) -> Value {
+    let value_type = if column.is_record() {
+        json_struct(column.children())
+    } else {
+        json!(primitive_type(&column.field_type).0)
+    };
+    let field_type = if column.is_repeated() {
+        json!({ "type": "array", "elementType": value_type, "containsNull": false })
+    } else {
+        value_type
+    };
+
+    let mut metadata = Map::new();
+    if let Some(description) = &column.description {
+        metadata.insert("description".to_string(), json!(description));
+    }
+    json!({
+        "name": column.name,
+        "type": field_type,
+        "nullable": nullable(column),
+        "metadata": metadata,
+    })
+}
+
+fn scala_string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+/// A `StructType(Seq(...))` expression whose fields are indented one level deeper than `indent`.
+fn scala_struct(columns: &[Column], indent: usize) -> String {
+    if columns.is_empty() {
+        return "StructType(Seq())".to_string();
+    }
+
+    let pad = "  ".repeat(indent + 1);
+    let fields: Vec<String> = columns
+        .iter()
+        .map(|column| format!("{}{}", pad, scala_field(column, indent + 1)))
+        .collect();
+    format!(
+        "StructType(Seq(\n{}\n{}))",
+        fields.join(",\n"),
+        "  ".repeat(indent)
+    )
+}
+
+fn scala_field(column: &Column, indent: usize) -> String {
+    let value_type = if column.is_record() {
+        scala_struct(column.children(), indent)
+    } else {
+        primitive_type(&column.field_type).1.to_string()
+    };
+    let field_type = if column.is_repeated() {
+        format!("ArrayType({}, containsNull = false)", value_type)
+    } else {
+        value_type
+    };
+
+    let mut field = format!(
+        "StructField({}, {}, nullable = {}",
+        scala_string(&column.name),
+        field_type,
+        nullable(column)
+    );
+    if let Some(description) = &column.description {
+        field.push_str(&format!(
+            ", new MetadataBuilder().putString(\"description\", {}).build()",
+            scala_string(description)
+        ));
+    }
+    field.push(')');
+    field
+}
+
+fn columns(table: &Table) -> &[Column] {
+    table
+        .schema
+        .as_ref()
+        .map(|schema| schema.fields.as_slice())
+        .unwrap_or(&[])
+}
+
+impl SchemaRenderer for SparkRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.schemas.clear();
+        if !self.scala {
+            return Ok(());
+        }
+
+        self.type_names = table_type_names(tables, &[])
+            .into_iter()
+            .map(|(reference, name)| {
+                let mut chars = name.chars();
+                let first = chars.next().map(|c| c.to_ascii_lowercase());
+                (
+                    reference,
+                    format!("{}{}Schema", first.unwrap_or('_'), chars.as_str()),
+                )
+            })
+            .collect();
+
+        writeln!(out, "// Generated by schema-searcher.")?;
+        writeln!(out, "import org.apache.spark.sql.types._")?;
+        writeln!(out)?;
+        write!(out, "object BigQuerySchemas {{")
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = table.table_reference.to_str();
+        if !self.scala {
+            self.schemas.push((reference, json_struct(columns(table))));
+            return Ok(());
+        }
+
+        let name = self
+            .type_names
+            .get(&reference)
+            .cloned()
+            .unwrap_or_else(|| "schema".to_string());
+        writeln!(out)?;
+        writeln!(out, "  // {}", reference)?;
+        writeln!(out, "  val {} = {}", name, scala_struct(columns(table), 1))
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        if self.scala {
+            return writeln!(out, "}}");
+        }
+
+        let document = if self.schemas.len() == 1 {
+            self.schemas.remove(0).1
+        } else {
+            Value::Object(self.schemas.drain(..).collect::<Map<String, Value>>())
+        };
+        serde_json::to_writer_pretty(&mut *out, &document)?;
+        writeln!(out)
+    }
+
+    fn extension(&self) -> &'static str {
+        if self.scala {
+            "scala"
+        } else {
+            "spark.json"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+
+    fn table(reference: &str) -> Table {
+        let mut table = Table::from_reference(reference.parse().unwrap());
+        table.schema = Some(
+            serde_json::from_value(json!({ "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key \"id\"" },
+                { "name": "local_at", "type": "DATETIME" },
+                { "name": "total", "type": "BIGNUMERIC" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                    { "name": "price", "type": "NUMERIC" }
+                ] }
+            ] }))
+            .unwrap(),
+        );
+        table
+    }
+
+    fn render(renderer: &mut SparkRenderer, tables: &[Table]) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, renderer, tables).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn scala_schemas_are_members_of_one_object() {
+        let output = render(&mut SparkRenderer::scala(), &[table("p.d.orders")]);
+
+        assert_eq!(
+            output,
+            r#"// Generated by schema-searcher.
+import org.apache.spark.sql.types._
+
+object BigQuerySchemas {
+  // p.d.orders
+  val ordersSchema = StructType(Seq(
+    StructField("id", LongType, nullable = false, new MetadataBuilder().putString("description", "Key \"id\"").build()),
+    StructField("local_at", TimestampNTZType, nullable = true),
+    StructField("total", StringType, nullable = true),
+    StructField("items", ArrayType(StructType(Seq(
+      StructField("price", DecimalType(38, 9), nullable = true)
+    )), containsNull = false), nullable = false)
+  ))
+}
+"#
+        );
+    }
+
+    #[test]
+    fn scala_names_are_unique_per_table() {
+        let output = render(
+            &mut SparkRenderer::scala(),
+            &[table("p.sales.orders"), table("p.ops.orders")],
+        );
+
+        assert!(output.contains("  val salesOrdersSchema = StructType(Seq(\n"));
+        assert!(output.contains("  val opsOrdersSchema = StructType(Seq(\n"));
+    }
+
+    #[test]
+    fn json_matches_the_spark_schema_format() {
+        let output = render(&mut SparkRenderer::json(), &[table("p.d.orders")]);
+        let schema: Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(schema["type"], "struct");
+        assert_eq!(
+            schema["fields"][0],
+            json!({
+                "name": "id",
+                "type": "long",
+                "nullable": false,
+                "metadata": { "description": "Key \"id\"" }
+            })
+        );
+        assert_eq!(schema["fields"][1]["type"], "timestamp_ntz");
+        assert_eq!(
+            schema["fields"][3],
+            json!({
+                "name": "items",
+                "type": {
+                    "type": "array",
+                    "elementType": {
+                        "type": "struct",
+                        "fields": [{ "name": "price", "type": "decimal(38,9)", "nullable": true, "metadata": {} }]
+                    },
+                    "containsNull": false
+                },
+                "nullable": false,
+                "metadata": {}
+            })
+        );
+    }
+
+    #[test]
+    fn several_json_schemas_are_keyed_by_reference() {
+        let output = render(
+            &mut SparkRenderer::json(),
+            &[table("p.d.orders"), table("p.d.refunds")],
+        );
+        let schemas: Value = serde_json::from_str(&output).unwrap();
+
+        let keys: Vec<&String> = schemas.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["p.d.orders", "p.d.refunds"]);
+    }
+}
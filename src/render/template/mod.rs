@@ -0,0 +1,225 @@
+//! Output from a user-supplied template in minijinja's Jinja2-like syntax.
+//!
+//! The template is rendered once with `tables`: every table as in the `json` format, plus `id`
+//! (`project.dataset.table`) and `columns` (the top-level columns, with nested fields under `fields`). RECORDs can
+//! be walked with a recursive loop, `{% for column in table.columns recursive %}...{{ loop(column.fields) }}`, or a
+//! macro that calls itself. `text.jinja` is the built-in template, used when no template is given.
+
+use crate::bigquery::types::Table;
+use crate::render::SchemaRenderer;
+use minijinja::{context, Environment};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{Error, Result, Write};
+use std::path::Path;
+
+const TEXT_TEMPLATE: &str = include_str!("text.jinja");
+const TEXT_TEMPLATE_NAME: &str = "text.jinja";
+
+/// Lines of template source shown on either side of the line an error points at.
+const ERROR_CONTEXT_LINES: usize = 2;
+
+pub struct TemplateRenderer {
+    env: Environment<'static>,
+    name: String,
+    source: String,
+    tables: Vec<Value>,
+}
+
+/// minijinja's message, which ends with `(in <template>:<line>)`, followed by the lines around it with the failing
+/// one marked.
+fn template_error(err: minijinja::Error, source: &str) -> Error {
+    let mut message = err.to_string();
+    if let Some(line) = err.line() {
+        let first = line.saturating_sub(ERROR_CONTEXT_LINES).max(1);
+        for (number, text) in source
+            .lines()
+            .enumerate()
+            .map(|(idx, text)| (idx + 1, text))
+            .skip(first - 1)
+            .take(line + ERROR_CONTEXT_LINES + 1 - first)
+        {
+            let marker = if number == line { '>' } else { ' ' };
+            message.push_str(&format!("\n{} {:>4} | {}", marker, number, text));
+        }
+    }
+    Error::other(message)
+}
+
+impl TemplateRenderer {
+    /// The built-in template, which reproduces the `text` format.
+    pub fn text() -> Self {
+        Self::new(TEXT_TEMPLATE_NAME.to_string(), TEXT_TEMPLATE.to_string())
+            .expect("the built-in template compiles")
+    }
+
+    /// Reads and compiles the template at `path`, so syntax errors show up before anything is fetched.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .map_err(|err| Error::new(err.kind(), format!("{}: {}", path.display(), err)))?;
+        Self::new(path.display().to_string(), source)
+    }
+
+    fn new(name: String, source: String) -> Result<Self> {
+        let mut env = Environment::new();
+        env.set_keep_trailing_newline(true);
+        env.add_template_owned(name.clone(), source.clone())
+            .map_err(|err| template_error(err, &source))?;
+        Ok(TemplateRenderer {
+            env,
+            name,
+            source,
+            tables: Vec::new(),
+        })
+    }
+}
+
+fn table_context(table: &Table) -> Result<Value> {
+    let mut value = serde_json::to_value(table)?;
+    let columns = value["schema"]["fields"].clone();
+    value["id"] = json!(table.table_reference.to_str());
+    value["columns"] = if columns.is_null() {
+        json!([])
+    } else {
+        columns
+    };
+    Ok(value)
+}
+
+impl SchemaRenderer for TemplateRenderer {
+    fn begin(&mut self, _out: &mut dyn Write, _tables: &[Table]) -> Result<()> {
+        self.tables.clear();
+        Ok(())
+    }
+
+    fn render_table(&mut self, _out: &mut dyn Write, table: &Table) -> Result<()> {
+        self.tables.push(table_context(table)?);
+        Ok(())
+    }
+
+    fn end(&mut self, out: &mut dyn Write) -> Result<()> {
+        let template = self
+            .env
+            .get_template(&self.name)
+            .map_err(|err| template_error(err, &self.source))?;
+        let tables = std::mem::take(&mut self.tables);
+        let rendered = template
+            .render(context! { tables })
+            .map_err(|err| template_error(err, &self.source))?;
+        write!(out, "{}", rendered)
+    }
+
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use crate::render::{Format, RenderOptions};
+
+    fn tables() -> Vec<Table> {
+        let mut orders = Table::from_reference("p.sales.orders".parse().unwrap());
+        orders.schema = Some(
+            serde_json::from_value(json!({ "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED" },
+                { "name": "note", "type": "STRING" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                    { "name": "sku", "type": "STRING", "mode": "NULLABLE" },
+                    { "name": "price", "type": "RECORD", "fields": [
+                        { "name": "amount", "type": "NUMERIC", "mode": "NULLABLE" }
+                    ] }
+                ] },
+                { "name": "empty", "type": "RECORD", "mode": "NULLABLE", "fields": [] }
+            ] }))
+            .unwrap(),
+        );
+        let view = Table::from_reference("p.sales.no_schema".parse().unwrap());
+        vec![orders, view]
+    }
+
+    fn render(format: Format, options: &RenderOptions) -> String {
+        let mut renderer = format.renderer(options).unwrap();
+        let mut out = Vec::new();
+        write_tables(&mut out, renderer.as_mut(), &tables()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn template_file(name: &str, source: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "schema-searcher-template-{}-{}.jinja",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn built_in_template_matches_the_text_format() {
+        let text = render(Format::Text, &RenderOptions::default());
+        let template = render(Format::Template, &RenderOptions::default());
+
+        assert!(text.contains("    |- amount (NUMERIC) [NULLABLE]\n"));
+        assert_eq!(template, text);
+    }
+
+    #[test]
+    fn user_templates_can_recurse_into_records() {
+        let path = template_file(
+            "recurse",
+            "{% for table in tables %}{{ table.id }}:\
+             {% for column in table.columns recursive %} {{ column.name }}\
+             {% if column.fields %} ({{ loop(column.fields) }} ){% endif %}{% endfor %}\n{% endfor %}",
+        );
+        let options = RenderOptions {
+            template: Some(path.clone()),
+            ..RenderOptions::default()
+        };
+        let output = render(Format::Template, &options);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            output,
+            "p.sales.orders: id note items ( sku price ( amount ) ) empty\np.sales.no_schema:\n"
+        );
+    }
+
+    #[test]
+    fn syntax_errors_point_at_the_line() {
+        let path = template_file("syntax", "line 1\nline 2\n{% for x in %}\nline 4\n");
+        let err = TemplateRenderer::from_file(&path)
+            .err()
+            .unwrap()
+            .to_string();
+        fs::remove_file(&path).unwrap();
+
+        assert!(err.contains(&format!("{}:3", path.display())), "{}", err);
+        assert!(err.ends_with(
+            "\n     1 | line 1\n     2 | line 2\n>    3 | {% for x in %}\n     4 | line 4"
+        ));
+    }
+
+    #[test]
+    fn render_errors_point_at_the_line() {
+        let path = template_file(
+            "render",
+            "{% for table in tables %}\n{{ table.id | nope }}\n{% endfor %}\n",
+        );
+        let options = RenderOptions {
+            template: Some(path.clone()),
+            ..RenderOptions::default()
+        };
+        let mut renderer = Format::Template.renderer(&options).unwrap();
+        let mut out = Vec::new();
+        let err = write_tables(&mut out, renderer.as_mut(), &tables())
+            .unwrap_err()
+            .to_string();
+        fs::remove_file(&path).unwrap();
+
+        assert!(err.contains(&format!("{}:2", path.display())), "{}", err);
+        assert!(err.contains("\n>    2 | {{ table.id | nope }}"));
+    }
+}
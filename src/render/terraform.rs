@@ -0,0 +1,587 @@
+//! Terraform configuration for the Google provider: a `google_bigquery_dataset` per dataset and a
+//! `google_bigquery_table` per table, each with an `import` block (Terraform 1.5+) so hand-made tables can be brought
+//! under management with `terraform plan -generate-config-out` or a plain `terraform apply`.
+//!
+//! Schemas are `jsonencode`d in the `bq` schema file format, with every field's mode spelled out so the plan doesn't
+//! show a diff. Views and materialized views get their query instead of a schema.
+//!
+//! Datasets get the location the API reported for their tables. When it's unknown, as for tables read from schema
+//! files, changes to `location` are ignored instead, since Terraform would otherwise plan to recreate the dataset in
+//! `US`. With `--per-table`, each dataset directory holds one module, and its dataset resource goes in `_dataset.tf`.
+
+use crate::bigquery::types::{Table, TableReference};
+use crate::render::bq_schema::BqField;
+use crate::render::SchemaRenderer;
+use serde::Serialize;
+use serde_json::ser::{Formatter, PrettyFormatter};
+use std::collections::{HashMap, HashSet};
+use std::io::{Error, Result, Write};
+
+#[derive(Default)]
+pub struct TerraformRenderer {
+    /// Resource names of the datasets, keyed by `project.dataset`.
+    dataset_names: HashMap<String, String>,
+    resource_names: HashSet<String>,
+    /// Set once the dataset resources are written to their own file by `dataset_files`, so the table documents
+    /// that follow only refer to them.
+    separate_datasets: bool,
+}
+
+/// The file holding a dataset's resource when each table gets its own file.
+const DATASET_FILE: &str = "_dataset.tf";
+
+/// An attribute value or a nested block.
+enum Item {
+    Attribute(&'static str, String),
+    Block(&'static str, Vec<Item>),
+}
+
+/// Escapes for the inside of a quoted string or heredoc, including `${` and `%{`, which would start a template.
+fn escape_template(text: &str) -> String {
+    text.replace("${", "$${").replace("%{", "%%{")
+}
+
+fn string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escape_template(&escaped))
+}
+
+/// A number attribute from the API's string-encoded integers, quoted if it isn't one.
+fn number(text: &str) -> String {
+    match text.parse::<i64>() {
+        Ok(value) => value.to_string(),
+        Err(_) => string(text),
+    }
+}
+
+/// Pretty-printed JSON with `"key" : value` pairs, which is how `terraform fmt` lays out JSON-style objects.
+struct HclFormatter(PrettyFormatter<'static>);
+
+impl Formatter for HclFormatter {
+    fn begin_array<W: ?Sized + Write>(&mut self, writer: &mut W) -> Result<()> {
+        self.0.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + Write>(&mut self, writer: &mut W) -> Result<()> {
+        self.0.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + Write>(&mut self, writer: &mut W, first: bool) -> Result<()> {
+        self.0.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> Result<()> {
+        self.0.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + Write>(&mut self, writer: &mut W) -> Result<()> {
+        self.0.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + Write>(&mut self, writer: &mut W) -> Result<()> {
+        self.0.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + Write>(&mut self, writer: &mut W, first: bool) -> Result<()> {
+        self.0.begin_object_key(writer, first)
+    }
+
+    fn begin_object_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> Result<()> {
+        writer.write_all(b" : ")
+    }
+
+    fn end_object_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> Result<()> {
+        self.0.end_object_value(writer)
+    }
+}
+
+fn heredoc(text: &str) -> String {
+    let text = escape_template(text.trim_end());
+    let mut delimiter = "EOT".to_string();
+    while text.lines().any(|line| line.trim() == delimiter) {
+        delimiter.push('_');
+    }
+    format!("<<-{}\n{}\n{}", delimiter, text, delimiter)
+}
+
+/// Resource names may only hold letters, digits, underscores and dashes, and can't start with a digit.
+fn resource_name(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !sanitized.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn dataset_key(reference: &TableReference) -> String {
+    format!("{}.{}", reference.project_id, reference.dataset_id)
+}
+
+impl TerraformRenderer {
+    /// Reserves `name`, or `name_2`, `name_3`, ... when another resource already has it.
+    fn unique_name(&mut self, name: String) -> String {
+        let mut candidate = name.clone();
+        let mut suffix = 2;
+        while !self.resource_names.insert(candidate.clone()) {
+            candidate = format!("{}_{}", name, suffix);
+            suffix += 1;
+        }
+        candidate
+    }
+}
+
+/// Writes items like `terraform fmt` does: the `=` of consecutive single-line attributes are aligned, and an
+/// attribute spanning several lines stands on its own, unless it's a heredoc.
+fn write_items(out: &mut dyn Write, items: &[Item], indent: usize) -> Result<()> {
+    let pad = " ".repeat(indent);
+    let mut idx = 0;
+    while idx < items.len() {
+        match &items[idx] {
+            Item::Block(name, children) => {
+                writeln!(out, "{}{} {{", pad, name)?;
+                write_items(out, children, indent + 2)?;
+                writeln!(out, "{}}}", pad)?;
+                idx += 1;
+            }
+            Item::Attribute(..) => {
+                let mut run: Vec<(&str, &String)> = Vec::new();
+                for item in &items[idx..] {
+                    match item {
+                        Item::Attribute(key, value)
+                            if !value.contains('\n') || value.starts_with("<<") =>
+                        {
+                            run.push((*key, value))
+                        }
+                        Item::Attribute(key, value) if run.is_empty() => {
+                            run.push((*key, value));
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+                let width = run.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+                for (key, value) in &run {
+                    let value = value.replace('\n', &format!("\n{}", pad));
+                    writeln!(out, "{}{:width$} = {}", pad, key, value, width = width)?;
+                }
+                idx += run.len();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_resource(
+    out: &mut dyn Write,
+    resource_type: &str,
+    name: &str,
+    items: &[Item],
+    import_id: &str,
+) -> Result<()> {
+    writeln!(out, "\nresource \"{}\" \"{}\" {{", resource_type, name)?;
+    write_items(out, items, 2)?;
+    writeln!(out, "}}")?;
+    writeln!(out, "\nimport {{")?;
+    writeln!(out, "  to = {}.{}", resource_type, name)?;
+    writeln!(out, "  id = {}", string(import_id))?;
+    writeln!(out, "}}")
+}
+
+fn table_items(table: &Table, dataset_name: &str) -> Result<Vec<Item>> {
+    let reference = &table.table_reference;
+    let mut items = vec![
+        Item::Attribute("project", string(&reference.project_id)),
+        Item::Attribute(
+            "dataset_id",
+            format!("google_bigquery_dataset.{}.dataset_id", dataset_name),
+        ),
+        Item::Attribute("table_id", string(&reference.table_id)),
+    ];
+    if let Some(description) = &table.description {
+        items.push(Item::Attribute("description", string(description)));
+    }
+    if !table.labels.is_empty() {
+        let width = table.labels.keys().map(|key| string(key).len()).max();
+        let labels: Vec<String> = table
+            .labels
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "  {:width$} = {}",
+                    string(key),
+                    string(value),
+                    width = width.unwrap_or(0)
+                )
+            })
+            .collect();
+        items.push(Item::Attribute(
+            "labels",
+            format!("{{\n{}\n}}", labels.join("\n")),
+        ));
+    }
+    if let Some(clustering) = &table.clustering {
+        let fields: Vec<String> = clustering.fields.iter().map(|f| string(f)).collect();
+        items.push(Item::Attribute(
+            "clustering",
+            format!("[{}]", fields.join(", ")),
+        ));
+    }
+
+    if let Some(view) = &table.view {
+        items.push(Item::Block(
+            "view",
+            vec![
+                Item::Attribute("query", heredoc(&view.query)),
+                Item::Attribute(
+                    "use_legacy_sql",
+                    view.use_legacy_sql.unwrap_or(false).to_string(),
+                ),
+            ],
+        ));
+        return Ok(items);
+    }
+    if let Some(view) = &table.materialized_view {
+        items.push(Item::Block(
+            "materialized_view",
+            vec![Item::Attribute("query", heredoc(&view.query))],
+        ));
+        return Ok(items);
+    }
+
+    if let Some(partitioning) = &table.time_partitioning {
+        let mut block = vec![Item::Attribute(
+            "type",
+            string(&partitioning.partition_type),
+        )];
+        if let Some(field) = &partitioning.field {
+            block.push(Item::Attribute("field", string(field)));
+        }
+        if let Some(expiration_ms) = &partitioning.expiration_ms {
+            block.push(Item::Attribute("expiration_ms", number(expiration_ms)));
+        }
+        items.push(Item::Block("time_partitioning", block));
+    }
+    if let Some(partitioning) = &table.range_partitioning {
+        let range = &partitioning.range;
+        items.push(Item::Block(
+            "range_partitioning",
+            vec![
+                Item::Attribute("field", string(&partitioning.field)),
+                Item::Block(
+                    "range",
+                    vec![
+                        Item::Attribute("start", number(&range.start)),
+                        Item::Attribute("end", number(&range.end)),
+                        Item::Attribute("interval", number(&range.interval)),
+                    ],
+                ),
+            ],
+        ));
+    }
+
+    if let Some(schema) = &table.schema {
+        let fields: Vec<BqField> = schema.fields.iter().map(BqField::from).collect();
+        let mut json = Vec::new();
+        fields.serialize(&mut serde_json::Serializer::with_formatter(
+            &mut json,
+            HclFormatter(PrettyFormatter::new()),
+        ))?;
+        let json = String::from_utf8(json).map_err(Error::other)?;
+        items.push(Item::Attribute(
+            "schema",
+            format!("jsonencode({})", escape_template(&json)),
+        ));
+    }
+    Ok(items)
+}
+
+impl TerraformRenderer {
+    /// Names a resource for every dataset in `tables` and, when `write` is set, writes the resources to `out`.
+    fn datasets(&mut self, out: &mut dyn Write, tables: &[&Table], write: bool) -> Result<()> {
+        self.dataset_names.clear();
+        self.resource_names.clear();
+
+        let mut dataset_projects: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for table in tables {
+            let reference = &table.table_reference;
+            dataset_projects
+                .entry(&reference.dataset_id)
+                .or_default()
+                .insert(&reference.project_id);
+        }
+
+        for table in tables {
+            let reference = &table.table_reference;
+            let key = dataset_key(reference);
+            if self.dataset_names.contains_key(&key) {
+                continue;
+            }
+
+            // Qualify with the project only when the dataset ID is selected from more than one project.
+            let name = if dataset_projects[reference.dataset_id.as_str()].len() > 1 {
+                format!("{}_{}", reference.project_id, reference.dataset_id)
+            } else {
+                reference.dataset_id.clone()
+            };
+            let name = self.unique_name(resource_name(&name));
+            self.dataset_names.insert(key.clone(), name.clone());
+            if !write {
+                continue;
+            }
+
+            let mut items = vec![
+                Item::Attribute("project", string(&reference.project_id)),
+                Item::Attribute("dataset_id", string(&reference.dataset_id)),
+            ];
+            // `location` forces a new dataset when it changes, so a guessed one would plan to recreate the dataset.
+            // Without one, e.g. for tables read from schema files, the dataset's actual location is left alone.
+            let location = tables
+                .iter()
+                .filter(|other| dataset_key(&other.table_reference) == key)
+                .find_map(|other| other.location.as_ref());
+            match location {
+                Some(location) => items.push(Item::Attribute("location", string(location))),
+                None => items.push(Item::Block(
+                    "lifecycle",
+                    vec![Item::Attribute("ignore_changes", "[location]".to_string())],
+                )),
+            }
+            write_resource(
+                out,
+                "google_bigquery_dataset",
+                &name,
+                &items,
+                &format!(
+                    "projects/{}/datasets/{}",
+                    reference.project_id, reference.dataset_id
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl SchemaRenderer for TerraformRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        writeln!(out, "# Generated by schema-searcher.")?;
+        let tables: Vec<&Table> = tables.iter().collect();
+        let write = !self.separate_datasets;
+        self.datasets(out, &tables, write)
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        let dataset_name = self.dataset_names[&dataset_key(reference)].clone();
+        let name = self.unique_name(resource_name(&format!(
+            "{}_{}",
+            dataset_name, reference.table_id
+        )));
+
+        write_resource(
+            out,
+            "google_bigquery_table",
+            &name,
+            &table_items(table, &dataset_name)?,
+            &format!(
+                "projects/{}/datasets/{}/tables/{}",
+                reference.project_id, reference.dataset_id, reference.table_id
+            ),
+        )
+    }
+
+    fn extension(&self) -> &'static str {
+        "tf"
+    }
+
+    fn default_file_name(&self) -> String {
+        "bigquery.tf".to_string()
+    }
+
+    fn dataset_files(&mut self, tables: &[&Table]) -> Result<Vec<(String, String)>> {
+        self.separate_datasets = true;
+        let mut contents = Vec::new();
+        writeln!(contents, "# Generated by schema-searcher.")?;
+        self.datasets(&mut contents, tables, true)?;
+        let contents = String::from_utf8(contents).map_err(Error::other)?;
+        Ok(vec![(DATASET_FILE.to_string(), contents)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::{write_table_tree, write_tables};
+    use serde_json::json;
+    use std::fs;
+
+    fn table(reference: &str, extra: serde_json::Value) -> Table {
+        let reference: TableReference = reference.parse().unwrap();
+        let mut table = json!({
+            "tableReference": reference,
+            "schema": { "fields": [
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key" },
+                { "name": "day", "type": "DATE" }
+            ] }
+        });
+        table
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(table).unwrap()
+    }
+
+    fn render(tables: &[Table]) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut TerraformRenderer::default(), tables).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn tables_are_resources_with_import_blocks() {
+        let orders = table(
+            "p.sales.orders",
+            json!({
+                "description": "Orders, ${not} a template",
+                "location": "EU",
+                "labels": { "team": "sales", "cost-center": "42" },
+                "timePartitioning": { "type": "DAY", "field": "day", "expirationMs": "86400000" },
+                "clustering": { "fields": ["id"] }
+            }),
+        );
+
+        assert_eq!(
+            render(&[orders]),
+            r#"# Generated by schema-searcher.
+
+resource "google_bigquery_dataset" "sales" {
+  project    = "p"
+  dataset_id = "sales"
+  location   = "EU"
+}
+
+import {
+  to = google_bigquery_dataset.sales
+  id = "projects/p/datasets/sales"
+}
+
+resource "google_bigquery_table" "sales_orders" {
+  project     = "p"
+  dataset_id  = google_bigquery_dataset.sales.dataset_id
+  table_id    = "orders"
+  description = "Orders, $${not} a template"
+  labels = {
+    "cost-center" = "42"
+    "team"        = "sales"
+  }
+  clustering = ["id"]
+  time_partitioning {
+    type          = "DAY"
+    field         = "day"
+    expiration_ms = 86400000
+  }
+  schema = jsonencode([
+    {
+      "description" : "Key",
+      "mode" : "REQUIRED",
+      "name" : "id",
+      "type" : "INTEGER"
+    },
+    {
+      "mode" : "NULLABLE",
+      "name" : "day",
+      "type" : "DATE"
+    }
+  ])
+}
+
+import {
+  to = google_bigquery_table.sales_orders
+  id = "projects/p/datasets/sales/tables/orders"
+}
+"#
+        );
+    }
+
+    #[test]
+    fn unknown_locations_are_ignored_instead_of_guessed() {
+        let output = render(&[table("p.sales.orders", json!({}))]);
+
+        assert!(!output.contains("location ="));
+        assert!(output.contains(
+            "  dataset_id = \"sales\"\n  lifecycle {\n    ignore_changes = [location]\n  }\n}\n"
+        ));
+    }
+
+    #[test]
+    fn views_get_their_query_as_a_heredoc() {
+        let view = table(
+            "p.sales.recent",
+            json!({ "view": { "query": "SELECT *\nFROM orders\nEOT\n", "useLegacySql": false } }),
+        );
+        let output = render(&[view]);
+
+        assert!(output.contains(
+            "  view {\n    query          = <<-EOT_\n    SELECT *\n    FROM orders\n    EOT\n    EOT_\n    use_legacy_sql = false\n  }\n"
+        ));
+        assert!(!output.contains("schema ="));
+    }
+
+    #[test]
+    fn datasets_are_qualified_when_their_ids_collide() {
+        let output = render(&[
+            table("p1.sales.orders", json!({})),
+            table("p2.sales.orders", json!({})),
+        ]);
+
+        assert!(output.contains("resource \"google_bigquery_dataset\" \"p1_sales\" {"));
+        assert!(output.contains("resource \"google_bigquery_dataset\" \"p2_sales\" {"));
+        assert!(output.contains("resource \"google_bigquery_table\" \"p2_sales_orders\" {"));
+        assert!(output.contains("  dataset_id = google_bigquery_dataset.p2_sales.dataset_id\n"));
+    }
+
+    #[test]
+    fn per_table_files_share_a_dataset_file() {
+        let dir =
+            std::env::temp_dir().join(format!("schema-searcher-terraform-{}", std::process::id()));
+        write_table_tree(
+            &dir,
+            &mut TerraformRenderer::default(),
+            &[
+                table("p.sales.orders", json!({})),
+                table("p.sales.refunds", json!({})),
+            ],
+        )
+        .unwrap();
+        let dataset_dir = dir.join("p/sales");
+        let dataset = fs::read_to_string(dataset_dir.join(DATASET_FILE)).unwrap();
+        let orders = fs::read_to_string(dataset_dir.join("orders.tf")).unwrap();
+        let refunds = fs::read_to_string(dataset_dir.join("refunds.tf")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            dataset
+                .matches("resource \"google_bigquery_dataset\" \"sales\"")
+                .count(),
+            1
+        );
+        for table in [&orders, &refunds] {
+            assert!(!table.contains("resource \"google_bigquery_dataset\""));
+            assert!(table.contains("  dataset_id = google_bigquery_dataset.sales.dataset_id\n"));
+        }
+        assert!(refunds.contains("resource \"google_bigquery_table\" \"sales_refunds\" {"));
+    }
+}
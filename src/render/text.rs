@@ -0,0 +1,58 @@
+use crate::bigquery::types::{Column, Table};
+use crate::render::SchemaRenderer;
+use std::io::{Result, Write};
+
+/// The original plain text format:
+///
+/// ```text
+/// === Table: project.dataset.table ===
+/// |- column_name (TYPE) [MODE]
+///   |- nested_field (TYPE) [MODE]
+/// ```
+pub struct TextRenderer;
+
+fn write_column(out: &mut dyn Write, column: &Column, indent: usize) -> Result<()> {
+    let indent_str = " ".repeat(indent);
+    writeln!(
+        out,
+        "{}|- {} ({}) [{}]",
+        indent_str,
+        column.name,
+        column.field_type,
+        column.mode.as_deref().unwrap_or("REQUIRED")
+    )?;
+
+    // If this is a RECORD type, print its nested fields
+    if let Some(fields) = &column.fields {
+        for field in fields {
+            write_column(out, field, indent + 2)?; // Increase indentation for nested fields
+        }
+    }
+
+    Ok(())
+}
+
+impl SchemaRenderer for TextRenderer {
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        writeln!(out, "\n=== Table: {} ===", table.table_reference.to_str())?;
+
+        match &table.schema {
+            Some(schema) => {
+                for column in &schema.fields {
+                    write_column(out, column, 0)?;
+                }
+            }
+            None => writeln!(
+                out,
+                "No schema available for {}",
+                table.table_reference.to_str()
+            )?,
+        }
+
+        Ok(())
+    }
+
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+}
@@ -0,0 +1,274 @@
+//! TypeScript interfaces for table rows. RECORD columns become their own interfaces and REPEATED columns arrays.
+//! INT64 and FLOAT64 are `number`; NUMERIC, BIGNUMERIC, BYTES and the date and time types are `string`, as the
+//! BigQuery REST API returns them.
+
+use crate::bigquery::types::{Column, Table};
+use crate::render::{pascal_case, table_type_names, unique_name, SchemaRenderer};
+use std::collections::{HashMap, HashSet};
+use std::io::{Result, Write};
+
+#[derive(Default)]
+pub struct TypeScriptRenderer {
+    /// Interface name per table, see `table_type_names`.
+    interface_names: HashMap<String, String>,
+    /// Every interface name in the file so far, so nested interface names don't clash.
+    type_names: HashSet<String>,
+}
+
+fn scalar_type(field_type: &str) -> &'static str {
+    match field_type {
+        "INTEGER" | "INT64" | "FLOAT" | "FLOAT64" => "number",
+        "BOOLEAN" | "BOOL" => "boolean",
+        "JSON" => "unknown",
+        _ => "string",
+    }
+}
+
+/// A double-quoted string literal. Non-ASCII characters are kept as they are, which TypeScript source allows.
+fn string_literal(text: &str) -> String {
+    let mut literal = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                literal.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+fn property_name(name: &str) -> String {
+    let valid = name.chars().enumerate().all(|(idx, c)| {
+        c == '_' || c == '$' || c.is_ascii_alphabetic() || (idx > 0 && c.is_ascii_digit())
+    });
+    if valid && !name.is_empty() {
+        name.to_string()
+    } else {
+        string_literal(name)
+    }
+}
+
+fn write_doc(out: &mut dyn Write, text: &str, indent: &str) -> Result<()> {
+    let text = text.replace("*/", "*\\/");
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() == 1 {
+        return writeln!(out, "{}/** {} */", indent, lines[0]);
+    }
+
+    writeln!(out, "{}/**", indent)?;
+    for line in lines {
+        let line = line.trim_end();
+        if line.is_empty() {
+            writeln!(out, "{} *", indent)?;
+        } else {
+            writeln!(out, "{} * {}", indent, line)?;
+        }
+    }
+    writeln!(out, "{} */", indent)
+}
+
+/// Writes the interface, then the interfaces for its RECORD columns. Nested interfaces are named `{Parent}{Column}`,
+/// with a number added when that's taken, e.g. by `foo_bar` and `fooBar` in the same RECORD or by another table's
+/// interface.
+fn write_interface(
+    out: &mut dyn Write,
+    type_names: &mut HashSet<String>,
+    name: &str,
+    description: Option<&String>,
+    columns: &[Column],
+) -> Result<()> {
+    writeln!(out)?;
+    if let Some(description) = description {
+        write_doc(out, description, "")?;
+    }
+    writeln!(out, "export interface {} {{", name)?;
+
+    let mut nested = Vec::new();
+    for column in columns {
+        let value_type = if column.is_record() {
+            let nested_name = unique_name(
+                type_names,
+                &format!("{}{}", name, pascal_case(&column.name)),
+                "",
+            );
+            nested.push((nested_name.clone(), column));
+            nested_name
+        } else {
+            scalar_type(&column.field_type).to_string()
+        };
+        let property_type = if column.is_repeated() {
+            format!("{}[]", value_type)
+        } else if column.is_required() {
+            value_type
+        } else {
+            format!("{} | null", value_type)
+        };
+
+        if let Some(description) = &column.description {
+            write_doc(out, description, "  ")?;
+        }
+        writeln!(out, "  {}: {};", property_name(&column.name), property_type)?;
+    }
+    writeln!(out, "}}")?;
+
+    for (nested_name, column) in nested {
+        write_interface(
+            out,
+            type_names,
+            &nested_name,
+            column.description.as_ref(),
+            column.children(),
+        )?;
+    }
+    Ok(())
+}
+
+impl SchemaRenderer for TypeScriptRenderer {
+    fn begin(&mut self, out: &mut dyn Write, tables: &[Table]) -> Result<()> {
+        self.interface_names = table_type_names(tables, &[]);
+        self.type_names = self.interface_names.values().cloned().collect();
+        writeln!(out, "// Generated by schema-searcher.")
+    }
+
+    fn render_table(&mut self, out: &mut dyn Write, table: &Table) -> Result<()> {
+        let reference = &table.table_reference;
+        let name = self
+            .interface_names
+            .get(&reference.to_str())
+            .cloned()
+            .unwrap_or_else(|| pascal_case(&reference.table_id));
+        let description = match &table.description {
+            Some(description) => format!("`{}`\n\n{}", reference.to_str(), description),
+            None => format!("`{}`", reference.to_str()),
+        };
+        let columns = table
+            .schema
+            .as_ref()
+            .map(|schema| schema.fields.as_slice())
+            .unwrap_or(&[]);
+
+        write_interface(
+            out,
+            &mut self.type_names,
+            &name,
+            Some(&description),
+            columns,
+        )
+    }
+
+    fn extension(&self) -> &'static str {
+        "ts"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::writer::write_tables;
+    use serde_json::json;
+
+    fn table(reference: &str, fields: serde_json::Value) -> Table {
+        let mut table = Table::from_reference(reference.parse().unwrap());
+        table.schema = Some(serde_json::from_value(json!({ "fields": fields })).unwrap());
+        table
+    }
+
+    fn render(tables: &[Table]) -> String {
+        let mut out = Vec::new();
+        write_tables(&mut out, &mut TypeScriptRenderer::default(), tables).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn columns_map_to_nullable_and_array_types() {
+        let output = render(&[table(
+            "p.d.orders",
+            json!([
+                { "name": "id", "type": "INTEGER", "mode": "REQUIRED", "description": "Key" },
+                { "name": "total", "type": "NUMERIC" },
+                { "name": "tags", "type": "STRING", "mode": "REPEATED" },
+                { "name": "order-id", "type": "STRING" },
+                { "name": "items", "type": "RECORD", "mode": "REPEATED", "fields": [
+                    { "name": "sku", "type": "STRING", "mode": "REQUIRED" }
+                ] }
+            ]),
+        )]);
+
+        assert_eq!(
+            output,
+            "// Generated by schema-searcher.
+
+/** `p.d.orders` */
+export interface Orders {
+  /** Key */
+  id: number;
+  total: string | null;
+  tags: string[];
+  \"order-id\": string | null;
+  items: OrdersItems[];
+}
+
+export interface OrdersItems {
+  sku: string;
+}
+"
+        );
+    }
+
+    #[test]
+    fn sibling_nested_names_are_unique() {
+        let sku = json!({ "name": "sku", "type": "STRING" });
+        let output = render(&[table(
+            "p.d.orders",
+            json!([
+                { "name": "order_items", "type": "RECORD", "fields": [sku] },
+                { "name": "orderItems", "type": "RECORD", "fields": [sku] }
+            ]),
+        )]);
+
+        assert!(output.contains("  order_items: OrdersOrderItems | null;\n"));
+        assert!(output.contains("  orderItems: OrdersOrderItems2 | null;\n"));
+        assert!(output.contains("export interface OrdersOrderItems {\n"));
+        assert!(output.contains("export interface OrdersOrderItems2 {\n"));
+    }
+
+    #[test]
+    fn nested_names_do_not_clash_with_tables() {
+        let sku = json!({ "name": "sku", "type": "STRING" });
+        let output = render(&[
+            table(
+                "p.d.orders",
+                json!([{ "name": "items", "type": "RECORD", "fields": [sku] }]),
+            ),
+            table("p.d.orders_items", json!([sku])),
+        ]);
+
+        assert!(output.contains("  items: OrdersItems2 | null;\n"));
+        assert!(output.contains("export interface OrdersItems2 {\n"));
+        assert_eq!(output.matches("export interface OrdersItems {").count(), 1);
+        assert!(output.contains("/** `p.d.orders_items` */\nexport interface OrdersItems {\n"));
+    }
+
+    #[test]
+    fn string_literals_are_escaped() {
+        assert_eq!(string_literal("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(string_literal("a\\b"), "\"a\\\\b\"");
+        assert_eq!(string_literal("a\nb\tc\r"), "\"a\\nb\\tc\\r\"");
+        assert_eq!(
+            string_literal("\u{0}\u{2028}\u{2029}"),
+            "\"\\u0000\\u2028\\u2029\""
+        );
+        assert_eq!(string_literal("café"), "\"café\"");
+
+        assert_eq!(property_name("$id_2"), "$id_2");
+        assert_eq!(property_name("2nd"), "\"2nd\"");
+        assert_eq!(property_name(""), "\"\"");
+    }
+}
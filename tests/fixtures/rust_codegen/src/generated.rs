@@ -0,0 +1,97 @@
+// Generated by schema-searcher.
+// Requires serde (derive), serde_json and chrono (serde) as dependencies.
+
+use serde::{Deserialize, Serialize};
+
+/// `acme.sales.orders`
+///
+/// One row per order.
+///
+/// Loaded hourly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Orders {
+    /// Primary key
+    pub order_id: i64,
+    #[serde(rename = "customerId")]
+    pub customer_id: Option<i64>,
+    pub r#type: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub order_date: Option<chrono::NaiveDate>,
+    pub local_time: Option<chrono::NaiveDateTime>,
+    pub cutoff: Option<chrono::NaiveTime>,
+    pub total: Option<String>,
+    pub exact_total: Option<String>,
+    pub score: Option<f64>,
+    pub is_gift: Option<bool>,
+    pub signature: Option<String>,
+    pub location: Option<String>,
+    pub payload: Option<serde_json::Value>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Line items
+    #[serde(default)]
+    pub items: Vec<OrdersItems>,
+    pub shipping: Option<OrdersShipping>,
+}
+
+/// Line items
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrdersItems {
+    pub sku: String,
+    pub quantity: Option<i64>,
+    #[serde(default)]
+    pub discounts: Vec<OrdersItemsDiscounts>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrdersItemsDiscounts {
+    pub code: Option<String>,
+    pub amount: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrdersShipping {
+    pub city: Option<String>,
+    #[serde(rename = "self")]
+    pub self_: Option<String>,
+}
+
+/// `acme.sales.events`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SalesEvents {
+    pub id: i64,
+}
+
+/// `acme.web.events`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebEvents {
+    pub id: String,
+    #[serde(rename = "pageUrl")]
+    pub page_url: Option<String>,
+    #[serde(rename = "page_url")]
+    pub page_url_2: Option<String>,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<WebEventsUserAgent>,
+    #[serde(rename = "user_agent")]
+    pub user_agent_2: Option<WebEventsUserAgent2>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebEventsUserAgent {
+    pub browser: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebEventsUserAgent2 {
+    pub os: Option<String>,
+}
+
+/// `acme.sales.vec`
+///
+/// Named like a prelude type, so its struct must not shadow it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vec2 {
+    pub label: Option<String>,
+    #[serde(default)]
+    pub values: Vec<String>,
+}
@@ -0,0 +1,35 @@
+pub mod generated;
+
+#[cfg(test)]
+mod tests {
+    use super::generated::*;
+
+    #[test]
+    fn deserializes_a_row() {
+        let row = r#"{
+            "order_id": 1,
+            "customerId": 7,
+            "type": "online",
+            "created_at": "2024-05-01T12:00:00Z",
+            "order_date": "2024-05-01",
+            "local_time": "2024-05-01T14:00:00",
+            "cutoff": "17:30:00",
+            "total": "12.50",
+            "exact_total": null,
+            "score": 0.5,
+            "is_gift": false,
+            "signature": null,
+            "location": "POINT(1 2)",
+            "payload": {"source": "app"},
+            "tags": ["a"],
+            "items": [{"sku": "X", "quantity": 2, "discounts": []}],
+            "shipping": {"city": "Oslo", "self": null}
+        }"#;
+
+        let order: Orders = serde_json::from_str(row).unwrap();
+        assert_eq!(order.customer_id, Some(7));
+        assert_eq!(order.r#type.as_deref(), Some("online"));
+        assert_eq!(order.items[0].discounts, vec![]);
+        assert_eq!(order.shipping.unwrap().self_, None);
+    }
+}
@@ -0,0 +1,103 @@
+//! Writes the fixture tables with `--per-table` in every format and checks each file is a complete document for its
+//! own table. Renderers are reused across the files, so this catches state left over from the previous table.
+
+use clap::ValueEnum;
+use schema_searcher::io::writer::write_table_tree;
+use schema_searcher::render::{json::read_tables, Format, RenderOptions};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+fn files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(self::files(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Parses the file with the parser its extension calls for, panicking with the file name on failure.
+fn check_parses(path: &Path, contents: &str) {
+    let name = path.file_name().unwrap().to_string_lossy();
+    if name.ends_with(".ndjson") {
+        for line in contents.lines() {
+            serde_json::from_str::<serde_json::Value>(line)
+                .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        }
+    } else if name.ends_with(".json") {
+        serde_json::from_str::<serde_json::Value>(contents)
+            .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+    } else if name.ends_with(".yml") || name.ends_with(".yaml") {
+        serde_yaml::from_str::<serde_yaml::Value>(contents)
+            .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+    } else if name.ends_with(".csv") || name.ends_with(".tsv") {
+        let delimiter = if name.ends_with(".tsv") { b'\t' } else { b',' };
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .from_reader(contents.as_bytes());
+        let columns = reader.headers().unwrap().len();
+        for record in reader.records() {
+            let record = record.unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+            assert_eq!(record.len(), columns, "{}", path.display());
+        }
+    } else {
+        assert!(!contents.trim().is_empty(), "{} is empty", path.display());
+    }
+}
+
+#[test]
+fn every_format_writes_one_document_per_table() {
+    let fixture =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rust_codegen/tables.json");
+    let tables = read_tables(BufReader::new(File::open(fixture).unwrap())).unwrap();
+    assert!(tables.len() > 1);
+
+    let root =
+        std::env::temp_dir().join(format!("schema-searcher-per-table-{}", std::process::id()));
+    for format in Format::value_variants() {
+        let name = format.to_possible_value().unwrap().get_name().to_string();
+        let dir = root.join(&name);
+        let mut renderer = format.renderer(&RenderOptions::default()).unwrap();
+        write_table_tree(&dir, renderer.as_mut(), &tables).unwrap();
+
+        for table in &tables {
+            let reference = &table.table_reference;
+            let dataset_dir = dir.join(&reference.project_id).join(&reference.dataset_id);
+            let table_files: Vec<PathBuf> = files(&dataset_dir)
+                .into_iter()
+                .filter(|path| {
+                    let relative = path.strip_prefix(&dataset_dir).unwrap().to_string_lossy();
+                    relative.starts_with(&format!("{}.", reference.table_id))
+                        || relative.starts_with(&format!("{}/", reference.table_id))
+                })
+                .collect();
+            assert!(
+                !table_files.is_empty(),
+                "{}: no files for {}",
+                name,
+                reference.to_str()
+            );
+
+            for path in table_files {
+                let contents = fs::read_to_string(&path).unwrap();
+                check_parses(&path, &contents);
+                for other in &tables {
+                    if other.table_reference.table_id != reference.table_id {
+                        assert!(
+                            !contents.contains(&other.table_reference.table_id),
+                            "{} mentions {}",
+                            path.display(),
+                            other.table_reference.to_str()
+                        );
+                    }
+                }
+            }
+        }
+    }
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,37 @@
+//! The Rust structs generated from `tests/fixtures/rust_codegen/tables.json` are checked in as
+//! `tests/fixtures/rust_codegen/src/generated.rs`. That crate is a default workspace member, so a plain `cargo build`
+//! or `cargo test` proves the generated code compiles. This test makes sure the checked-in file matches what the renderer generates today.
+//!
+//! Run with `UPDATE_FIXTURES=1` to regenerate the file after changing the renderer.
+
+use schema_searcher::io::writer::write_tables;
+use schema_searcher::render::{json::read_tables, Format, RenderOptions};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+#[test]
+fn generated_rust_matches_fixture_crate() {
+    let fixture_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/rust_codegen");
+    let tables = read_tables(BufReader::new(
+        File::open(fixture_dir.join("tables.json")).unwrap(),
+    ))
+    .unwrap();
+
+    let mut renderer = Format::Rust.renderer(&RenderOptions::default()).unwrap();
+    let mut generated = Vec::new();
+    write_tables(&mut generated, renderer.as_mut(), &tables).unwrap();
+    let generated = String::from_utf8(generated).unwrap();
+
+    let generated_path = fixture_dir.join("src/generated.rs");
+    if std::env::var_os("UPDATE_FIXTURES").is_some() {
+        fs::write(&generated_path, &generated).unwrap();
+    }
+
+    assert_eq!(
+        fs::read_to_string(&generated_path).unwrap(),
+        generated,
+        "{} is out of date, rerun with UPDATE_FIXTURES=1",
+        generated_path.display()
+    );
+}